@@ -0,0 +1,131 @@
+//! Benchmarks `LsColors::style_for_path` on real files, comparing a configuration that only needs
+//! the file type and suffix with one that needs the full metadata (permissions, link count, link
+//! targets). For the configuration that only styles suffixes, the metadata-free lookups
+//! (`LsColors::style_for_path_fast` and `LsColors::style_for_path_with_metadata` without
+//! metadata) are benchmarked, too. Also benchmarks `LsColors::style_for_str` on very long names,
+//! with short and long configured suffixes.
+//!
+//! Run with `cargo bench --bench style_for_path`.
+
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lscolors::LsColors;
+
+/// Number of files in the fixture directory.
+const NUM_FILES: usize = 1000;
+
+/// Minimum time spent per benchmark.
+const BENCH_TIME: Duration = Duration::from_secs(2);
+
+/// Suffixes used for the fixture files.
+const SUFFIXES: &[&str] = &["rs", "md", "tar.gz", "png", "txt", "o", "json", ""];
+
+/// Only suffixes: all default indicator styles are reset, so no file type is needed.
+const SUFFIX_ONLY: &str = "di=0:ln=0:pi=0:so=0:do=0:bd=0:cd=0:su=0:sg=0:ex=0:tw=0:ow=0:st=0:\
+    *.rs=01;33:*.md=35:*.tar.gz=01;31:*.png=01;35";
+
+/// Everything that requires the full metadata or an extra `stat` of symlink targets.
+const METADATA: &str = "mh=44:or=40;31;01:*.rs=01;33:*.md=35:*.tar.gz=01;31:*.png=01;35";
+
+/// Create the fixture files and return their paths.
+fn create_fixture(dir: &std::path::Path) -> Vec<PathBuf> {
+    (0..NUM_FILES)
+        .map(|i| {
+            let suffix = SUFFIXES[i % SUFFIXES.len()];
+            let path = dir.join(format!("file-{}.{}", i, suffix));
+            match i % 10 {
+                0 => fs::create_dir(&path).unwrap(),
+                #[cfg(unix)]
+                1 => std::os::unix::fs::symlink(dir.join("missing"), &path).unwrap(),
+                #[cfg(unix)]
+                2 => {
+                    use std::os::unix::fs::PermissionsExt;
+                    File::create(&path).unwrap();
+                    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+                }
+                _ => {
+                    File::create(&path).unwrap();
+                }
+            }
+            path
+        })
+        .collect()
+}
+
+fn bench<R>(name: &str, paths: &[PathBuf], style_for: impl Fn(&Path) -> R) {
+    // Warm up the file system caches
+    for path in paths {
+        black_box(style_for(path));
+    }
+
+    let start = Instant::now();
+    let mut files = 0;
+    while start.elapsed() < BENCH_TIME {
+        for path in paths {
+            black_box(style_for(black_box(path)));
+        }
+        files += paths.len();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<20} {:>12.0} files/sec",
+        name,
+        files as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn bench_str(name: &str, lscolors: &LsColors, names: &[String]) {
+    let start = Instant::now();
+    let mut files = 0;
+    while start.elapsed() < BENCH_TIME {
+        for name in names {
+            black_box(lscolors.style_for_str(black_box(name)));
+        }
+        files += names.len();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<20} {:>12.0} names/sec",
+        name,
+        files as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let tmp_dir = tempfile::tempdir().expect("temporary directory");
+    let paths = create_fixture(tmp_dir.path());
+
+    let suffix_only = LsColors::from_string(SUFFIX_ONLY);
+    let metadata = LsColors::from_string(METADATA);
+    bench("suffix only", &paths, |path| {
+        suffix_only.style_for_path(path)
+    });
+    bench("suffix only (fast)", &paths, |path| {
+        suffix_only.style_for_path_fast(path)
+    });
+    bench("without metadata", &paths, |path| {
+        suffix_only.style_for_path_with_metadata(path, None)
+    });
+    bench("metadata", &paths, |path| metadata.style_for_path(path));
+
+    let long_names: Vec<_> = SUFFIXES
+        .iter()
+        .map(|suffix| format!("{}.{}", "x".repeat(4096), suffix))
+        .collect();
+    let long_suffix = format!("{}:*.{}=01;34", SUFFIX_ONLY, "y".repeat(64));
+    bench_str(
+        "long names",
+        &LsColors::from_string(SUFFIX_ONLY),
+        &long_names,
+    );
+    bench_str(
+        "long suffixes",
+        &LsColors::from_string(&long_suffix),
+        &long_names,
+    );
+}
@@ -1,80 +1,257 @@
+use std::borrow::Cow;
 use std::env;
+use std::ffi::OsString;
 use std::io;
 use std::io::prelude::*;
+use std::io::IsTerminal;
 use std::path::Path;
+use std::process;
 
-use lscolors::{LsColors, Style};
-
-#[cfg(all(
-    not(feature = "nu-ansi-term"),
-    not(feature = "gnu_legacy"),
-    not(feature = "ansi_term"),
-    not(feature = "crossterm"),
-    not(feature = "owo-colors")
-))]
-compile_error!(
-    "one feature must be enabled: ansi_term, nu-ansi-term, crossterm, gnu_legacy, owo-colors"
-);
-
-fn print_path(handle: &mut dyn Write, ls_colors: &LsColors, path: &str) -> io::Result<()> {
-    for (component, style) in ls_colors.style_for_path_components(Path::new(path)) {
-        #[cfg(any(feature = "nu-ansi-term", feature = "gnu_legacy"))]
-        {
-            let ansi_style = style.map(Style::to_nu_ansi_term_style).unwrap_or_default();
-            write!(handle, "{}", ansi_style.paint(component.to_string_lossy()))?;
-        }
+use lscolors::{os_str_bytes, LsColors};
+
+/// Get a path from raw bytes (lossy on non-Unix platforms).
+fn bytes_to_path(bytes: &[u8]) -> Cow<'_, Path> {
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        Cow::Borrowed(Path::new(OsStr::from_bytes(bytes)))
+    }
+
+    #[cfg(not(unix))]
+    match String::from_utf8_lossy(bytes) {
+        Cow::Borrowed(s) => Cow::Borrowed(Path::new(s)),
+        Cow::Owned(s) => Cow::Owned(s.into()),
+    }
+}
 
-        #[cfg(feature = "ansi_term")]
-        {
-            let ansi_style = style.map(Style::to_ansi_term_style).unwrap_or_default();
-            write!(handle, "{}", ansi_style.paint(component.to_string_lossy()))?;
+fn print_path<W: Write>(
+    handle: &mut W,
+    ls_colors: &LsColors,
+    path: &Path,
+    options: &Options,
+    colorize: bool,
+) -> io::Result<()> {
+    if colorize && options.only_basename {
+        ls_colors.write_path_basename(handle, path)?;
+    } else if colorize {
+        ls_colors.write_path(handle, path)?;
+    } else {
+        handle.write_all(&os_str_bytes(path.as_os_str()))?;
+    }
+
+    if options.classify {
+        if let Some(c) = ls_colors.type_suffix_char_for_path(path) {
+            write!(handle, "{}", c)?;
         }
+    }
+    handle.write_all(if options.print0 { b"\0" } else { b"\n" })?;
+
+    Ok(())
+}
+
+/// Short usage information.
+const USAGE: &str =
+    "Usage: lscolors [-F] [-0] [--print0] [--only-basename] [--color WHEN] [--colors-env NAME | --colors STRING] [PATH...]";
+
+/// Help text for `--help`.
+const HELP: &str = "\
+Colorize paths using the LS_COLORS environment variable.
+
+Paths are read from the arguments, or line by line from stdin if there are none.
+
+Options:
+  -F, --classify         Append an indicator (one of /@*|=>) to the paths, like `ls -F`
+      --only-basename    Only colorize the last component of each path
+  -0, --read0            Read NUL-separated paths from stdin
+      --print0           Separate the output with NUL instead of newline characters
+      --color WHEN       Use colors: auto (default), always or never
+      --colors-env NAME  Read the colors from the NAME environment variable
+      --colors STRING    Use the given LS_COLORS-style string
+  -h, --help             Print this help
+  -V, --version          Print the version
+      --                 Treat all following arguments as paths";
+
+/// Where to read the colors from.
+enum ColorsSource {
+    /// An environment variable (`LS_COLORS` by default).
+    Env(OsString),
+    /// A literal `LS_COLORS`-style string.
+    String(OsString),
+}
+
+/// When to use colors.
+enum ColorWhen {
+    /// Only if stdout is a terminal, respecting `NO_COLOR` and `CLICOLOR_FORCE`.
+    Auto,
+    Always,
+    Never,
+}
 
-        #[cfg(feature = "crossterm")]
-        {
-            let ansi_style = style.map(Style::to_crossterm_style).unwrap_or_default();
-            write!(handle, "{}", ansi_style.apply(component.to_string_lossy()))?;
+/// Get the value of an environment variable, if it is set and not empty.
+fn env_non_empty(name: &str) -> Option<OsString> {
+    env::var_os(name).filter(|value| !value.is_empty())
+}
+
+/// Command-line options.
+struct Options {
+    colors: ColorsSource,
+    color: ColorWhen,
+    /// Append an indicator character like `ls -F`.
+    classify: bool,
+    /// Only style the last path component.
+    only_basename: bool,
+    /// Read NUL-separated paths from stdin.
+    read0: bool,
+    /// Separate the output with NUL instead of newline characters.
+    print0: bool,
+    /// Print the help text.
+    help: bool,
+    /// Print the version.
+    version: bool,
+    paths: Vec<OsString>,
+}
+
+impl Options {
+    /// Parse the command-line arguments (without the program name).
+    fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self, String> {
+        let mut options = Options {
+            colors: ColorsSource::Env("LS_COLORS".into()),
+            color: ColorWhen::Auto,
+            classify: false,
+            only_basename: false,
+            read0: false,
+            print0: false,
+            help: false,
+            version: false,
+            paths: vec![],
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.to_str() {
+                Some("--") => {
+                    options.paths.extend(args);
+                    break;
+                }
+                Some("-F" | "--classify") => {
+                    options.classify = true;
+                    continue;
+                }
+                Some("--only-basename") => {
+                    options.only_basename = true;
+                    continue;
+                }
+                Some("-0" | "--read0") => {
+                    options.read0 = true;
+                    continue;
+                }
+                Some("--print0") => {
+                    options.print0 = true;
+                    continue;
+                }
+                Some("-h" | "--help") => {
+                    options.help = true;
+                    continue;
+                }
+                Some("-V" | "--version") => {
+                    options.version = true;
+                    continue;
+                }
+                Some(arg) if arg.starts_with("--") => match arg.split_once('=') {
+                    Some((flag, value)) => (flag.to_owned(), Some(value.into())),
+                    None => (arg.to_owned(), None),
+                },
+                _ => {
+                    options.paths.push(arg);
+                    continue;
+                }
+            };
+
+            let value = || {
+                value
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for '{}'", flag))
+            };
+
+            match flag.as_str() {
+                "--colors-env" => options.colors = ColorsSource::Env(value()?),
+                "--colors" => options.colors = ColorsSource::String(value()?),
+                "--color" => {
+                    options.color = match value()?.to_str() {
+                        Some("auto") => ColorWhen::Auto,
+                        Some("always") => ColorWhen::Always,
+                        Some("never") => ColorWhen::Never,
+                        _ => return Err("'--color' must be one of auto, always, never".into()),
+                    }
+                }
+                _ => return Err(format!("unknown option '{}'", flag)),
+            }
         }
-        #[cfg(feature = "owo-colors")]
-        {
-            use owo_colors::OwoColorize;
-            let ansi_style = style.map(Style::to_owo_colors_style).unwrap_or_default();
-            write!(handle, "{}", component.to_string_lossy().style(ansi_style))?;
+
+        Ok(options)
+    }
+
+    /// Check whether the output should be colorized.
+    fn colorize(&self) -> bool {
+        match self.color {
+            ColorWhen::Always => true,
+            ColorWhen::Never => false,
+            ColorWhen::Auto => {
+                if env_non_empty("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    true
+                } else if env_non_empty("NO_COLOR").is_some() {
+                    false
+                } else {
+                    io::stdout().is_terminal()
+                }
+            }
         }
     }
-    writeln!(handle)?;
 
-    Ok(())
+    /// Get the colors to use.
+    fn ls_colors(&self) -> LsColors {
+        let colors = match &self.colors {
+            ColorsSource::Env(name) => env::var_os(name),
+            ColorsSource::String(colors) => Some(colors.clone()),
+        };
+
+        colors
+            .map(|s| LsColors::from_string(&s.to_string_lossy()))
+            .unwrap_or_default()
+    }
 }
 
-fn run() -> io::Result<()> {
-    let ls_colors = LsColors::from_env().unwrap_or_default();
+fn run(options: Options) -> io::Result<()> {
+    let ls_colors = options.ls_colors();
+    let colorize = options.colorize();
 
     let stdout = io::stdout();
     let mut stdout = stdout.lock();
 
-    let mut args = env::args();
-
-    if args.len() >= 2 {
-        // Skip program name
-        args.next();
-
-        for arg in args {
-            print_path(&mut stdout, &ls_colors, &arg)?;
+    if !options.paths.is_empty() {
+        for path in &options.paths {
+            print_path(&mut stdout, &ls_colors, Path::new(path), &options, colorize)?;
         }
     } else {
         let stdin = io::stdin();
         let mut buf = vec![];
+        let delimiter = if options.read0 { b'\0' } else { b'\n' };
 
-        while let Ok(size) = stdin.lock().read_until(b'\n', &mut buf) {
+        while let Ok(size) = stdin.lock().read_until(delimiter, &mut buf) {
             if size == 0 {
                 break;
             }
 
-            let path_str = String::from_utf8_lossy(&buf[..(buf.len() - 1)]);
+            // The last record may not be terminated
+            let path_bytes = buf.strip_suffix(&[delimiter]).unwrap_or(&buf);
             #[cfg(windows)]
-            let path_str = path_str.trim_end_matches('\r');
-            print_path(&mut stdout, &ls_colors, path_str.as_ref())?;
+            let path_bytes = match options.read0 {
+                false => path_bytes.strip_suffix(b"\r").unwrap_or(path_bytes),
+                true => path_bytes,
+            };
+            let path = bytes_to_path(path_bytes);
+            print_path(&mut stdout, &ls_colors, &path, &options, colorize)?;
 
             buf.clear();
         }
@@ -84,5 +261,22 @@ fn run() -> io::Result<()> {
 }
 
 fn main() {
-    run().ok();
+    let options = match Options::parse(env::args_os().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("lscolors: {}", err);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}\n\n{}", USAGE, HELP);
+        return;
+    } else if options.version {
+        println!("lscolors {}", env!("CARGO_PKG_VERSION"));
+        return;
+    }
+
+    run(options).ok();
 }
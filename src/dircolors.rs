@@ -0,0 +1,279 @@
+//! Parsing of `dircolors` databases.
+//!
+//! GNU `dircolors` reads its configuration from files like `~/.dircolors` or `/etc/DIR_COLORS`,
+//! which contain one `KEYWORD value` pair per line, for example
+//!
+//! ```text
+//! # Directories
+//! DIR 01;34
+//! .tar 01;31
+//! *.jpg 01;35
+//! ```
+//!
+//! Rules can be restricted to certain terminals by preceding them with one or more `TERM` or
+//! `COLORTERM` lines. See `man dir_colors` for more information.
+
+use std::borrow::Cow;
+
+/// Tracks whether the lines of a `dircolors` database apply to the current terminal. This
+/// follows the logic of GNU `dircolors`: consecutive `TERM` / `COLORTERM` lines form a block that
+/// is active if any of the patterns match, and lines before the first block always apply.
+pub struct TermFilter<'a> {
+    term: &'a str,
+    colorterm: &'a str,
+    state: TermState,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TermState {
+    /// Before any `TERM` line
+    Global,
+    /// In a block of `TERM` lines, none of which matched (yet)
+    No,
+    /// In a block of `TERM` lines, one of which matched
+    Sure,
+    /// After a matching block of `TERM` lines
+    Yes,
+}
+
+impl<'a> TermFilter<'a> {
+    pub fn new(term: &'a str, colorterm: &'a str) -> Self {
+        Self {
+            term,
+            colorterm,
+            state: TermState::Global,
+        }
+    }
+
+    /// Process a line, and return whether it should be applied.
+    pub fn apply(&mut self, keyword: &str, value: &str) -> bool {
+        let name = if keyword.eq_ignore_ascii_case("TERM") {
+            self.term
+        } else if keyword.eq_ignore_ascii_case("COLORTERM") {
+            self.colorterm
+        } else {
+            if self.state == TermState::Sure {
+                self.state = TermState::Yes;
+            }
+            return self.state != TermState::No;
+        };
+
+        if glob_match(value.as_bytes(), name.as_bytes()) {
+            self.state = TermState::Sure;
+        } else if self.state != TermState::Sure {
+            self.state = TermState::No;
+        }
+        false
+    }
+}
+
+/// Match a string against a shell-style pattern with `*` and `?` wildcards.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], text) || (!text.is_empty() && glob_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => glob_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+/// Split a line into its keyword and value. Returns `None` for blank lines, comments and lines
+/// without a value.
+pub fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    if line.starts_with('#') {
+        return None;
+    }
+
+    let (keyword, rest) = line.split_once(|c: char| c.is_ascii_whitespace())?;
+
+    // Like `dircolors`, everything after a `#` is a comment
+    let value = rest.split('#').next().unwrap_or_default().trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    Some((keyword, value))
+}
+
+/// Get the `LS_COLORS` key (like `di` or `*.tar`) for a keyword. Returns `None` for keywords
+/// that do not describe a style, like `TERM`, `COLOR` or `OPTIONS`.
+pub fn ls_colors_key(keyword: &str) -> Option<Cow<'_, str>> {
+    if keyword.starts_with('*') {
+        return Some(Cow::Borrowed(keyword));
+    }
+    if keyword.starts_with('.') {
+        return Some(Cow::Owned(format!("*{}", keyword)));
+    }
+
+    let key = match keyword.to_ascii_uppercase().as_str() {
+        "NORMAL" | "NORM" => "no",
+        "FILE" => "fi",
+        "RESET" => "rs",
+        "DIR" => "di",
+        "LNK" | "LINK" | "SYMLINK" => "ln",
+        "ORPHAN" => "or",
+        "MISSING" => "mi",
+        "FIFO" | "PIPE" => "pi",
+        "SOCK" => "so",
+        "BLK" | "BLOCK" => "bd",
+        "CHR" | "CHAR" => "cd",
+        "DOOR" => "do",
+        "EXEC" => "ex",
+        "LEFT" | "LEFTCODE" => "lc",
+        "RIGHT" | "RIGHTCODE" => "rc",
+        "END" | "ENDCODE" => "ec",
+        "SUID" | "SETUID" => "su",
+        "SGID" | "SETGID" => "sg",
+        "STICKY" => "st",
+        "OTHER_WRITABLE" | "OWR" => "ow",
+        "STICKY_OTHER_WRITABLE" | "OWT" => "tw",
+        "CAPABILITY" => "ca",
+        "MULTIHARDLINK" => "mh",
+        "CLRTOEOL" => "cl",
+        _ => return None,
+    };
+    Some(Cow::Borrowed(key))
+}
+
+/// Extract the value of `LS_COLORS` from the output of `dircolors -b` (`LS_COLORS='...';` and
+/// `export LS_COLORS`) or `dircolors -c` (`setenv LS_COLORS '...'`). Returns `None` if there is
+/// no assignment to `LS_COLORS`.
+pub fn parse_shell_assignment(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = line.trim_start();
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+
+        if let Some(value) = line.strip_prefix("LS_COLORS=") {
+            Some(parse_shell_word(value))
+        } else {
+            let value = line.strip_prefix("setenv")?;
+            let value = value.trim_start().strip_prefix("LS_COLORS")?;
+            value
+                .starts_with(|c: char| c.is_ascii_whitespace())
+                .then(|| parse_shell_word(value.trim_start()))
+        }
+    })
+}
+
+/// Parse a (possibly quoted) shell word, up to the first unquoted whitespace or `;`.
+fn parse_shell_word(input: &str) -> String {
+    let mut word = String::new();
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => word.extend(chars.by_ref().take_while(|&c| c != '\'')),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.extend(chars.next()),
+            ';' => break,
+            c if c.is_ascii_whitespace() => break,
+            c => word.push(c),
+        }
+    }
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(Some(("DIR", "01;34")), parse_line("DIR 01;34"));
+        assert_eq!(Some(("DIR", "01;34")), parse_line("  DIR\t01;34  "));
+        assert_eq!(
+            Some(("DIR", "01;34")),
+            parse_line("DIR 01;34 # directories")
+        );
+        assert_eq!(Some((".tar", "01;31")), parse_line(".tar 01;31"));
+        assert_eq!(None, parse_line(""));
+        assert_eq!(None, parse_line("   "));
+        assert_eq!(None, parse_line("# DIR 01;34"));
+        assert_eq!(None, parse_line("DIR"));
+        assert_eq!(None, parse_line("DIR # comment"));
+    }
+
+    #[test]
+    fn shell_assignments() {
+        let bourne = "LS_COLORS='rs=0:di=01;34:*.tar=01;31:';\nexport LS_COLORS\n";
+        assert_eq!(
+            Some("rs=0:di=01;34:*.tar=01;31:".into()),
+            parse_shell_assignment(bourne)
+        );
+
+        let csh = "setenv LS_COLORS 'rs=0:di=01;34:*.tar=01;31:'\n";
+        assert_eq!(
+            Some("rs=0:di=01;34:*.tar=01;31:".into()),
+            parse_shell_assignment(csh)
+        );
+
+        // Quotes in the value
+        let quoted = "LS_COLORS='*.x'\\''y=01:';\nexport LS_COLORS\n";
+        assert_eq!(Some("*.x'y=01:".into()), parse_shell_assignment(quoted));
+
+        assert_eq!(
+            Some("di=01;34".into()),
+            parse_shell_assignment("# colors\nexport LS_COLORS=\"di=01;34\" # comment")
+        );
+        assert_eq!(
+            Some("di=34".into()),
+            parse_shell_assignment("LS_COLORS=di=34")
+        );
+
+        assert_eq!(None, parse_shell_assignment("export LS_COLORS\n"));
+        assert_eq!(None, parse_shell_assignment("setenv LS_COLORSX 'di=34'"));
+        assert_eq!(None, parse_shell_assignment(""));
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match(b"xterm*", b"xterm"));
+        assert!(glob_match(b"xterm*", b"xterm-256color"));
+        assert!(glob_match(b"*color*", b"xterm-256color"));
+        assert!(glob_match(b"vt1??", b"vt100"));
+        assert!(!glob_match(b"vt1??", b"vt1000"));
+        assert!(!glob_match(b"xterm*", b"linux"));
+        assert!(!glob_match(b"linux", b"linux2"));
+    }
+
+    #[test]
+    fn term_filter() {
+        let mut filter = TermFilter::new("xterm-256color", "");
+        assert!(filter.apply("DIR", "01;34"));
+        assert!(!filter.apply("TERM", "linux"));
+        assert!(!filter.apply("DIR", "01;34"));
+        assert!(!filter.apply("TERM", "xterm*"));
+        assert!(!filter.apply("TERM", "screen*"));
+        assert!(filter.apply("DIR", "01;34"));
+        assert!(!filter.apply("COLORTERM", "?*"));
+        assert!(!filter.apply("DIR", "01;34"));
+
+        let mut filter = TermFilter::new("dumb", "truecolor");
+        assert!(!filter.apply("COLORTERM", "?*"));
+        assert!(filter.apply("DIR", "01;34"));
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(Some("di".into()), ls_colors_key("DIR"));
+        assert_eq!(Some("di".into()), ls_colors_key("dir"));
+        assert_eq!(Some("ln".into()), ls_colors_key("SYMLINK"));
+        assert_eq!(Some("tw".into()), ls_colors_key("STICKY_OTHER_WRITABLE"));
+        assert_eq!(Some("*.tar".into()), ls_colors_key(".tar"));
+        assert_eq!(Some("*README".into()), ls_colors_key("*README"));
+        assert_eq!(None, ls_colors_key("TERM"));
+        assert_eq!(None, ls_colors_key("COLOR"));
+        assert_eq!(None, ls_colors_key("OPTIONS"));
+    }
+}
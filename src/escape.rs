@@ -0,0 +1,92 @@
+//! Decoding of escape sequences in `LS_COLORS` values.
+//!
+//! GNU `ls` allows values like `lc=\e[` or `ec=^[[0m`. Supported are C-style escapes
+//! (`\a`, `\b`, `\e`, `\f`, `\n`, `\r`, `\t`, `\v`, `\?` for DEL and `\_` for a space), octal
+//! (`\033`) and hexadecimal (`\x1b`) escapes, as well as caret notation (`^[`, `^?`).
+
+/// Decode all escape sequences in the given value.
+pub fn unescape(input: &str) -> String {
+    let mut bytes = input.bytes().peekable();
+    let mut output = Vec::with_capacity(input.len());
+
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\\' => match bytes.next() {
+                Some(d @ b'0'..=b'7') => {
+                    let mut n = d - b'0';
+                    for _ in 0..2 {
+                        match bytes.peek() {
+                            Some(d @ b'0'..=b'7') => {
+                                n = n.wrapping_mul(8).wrapping_add(d - b'0');
+                                bytes.next();
+                            }
+                            _ => break,
+                        }
+                    }
+                    output.push(n);
+                }
+                Some(b'x') => {
+                    let mut n = 0u8;
+                    for _ in 0..2 {
+                        match bytes.peek().and_then(|d| (*d as char).to_digit(16)) {
+                            Some(d) => {
+                                n = n.wrapping_mul(16).wrapping_add(d as u8);
+                                bytes.next();
+                            }
+                            None => break,
+                        }
+                    }
+                    output.push(n);
+                }
+                Some(b'a') => output.push(0x07),
+                Some(b'b') => output.push(0x08),
+                Some(b'e') => output.push(0x1b),
+                Some(b'f') => output.push(0x0c),
+                Some(b'n') => output.push(b'\n'),
+                Some(b'r') => output.push(b'\r'),
+                Some(b't') => output.push(b'\t'),
+                Some(b'v') => output.push(0x0b),
+                Some(b'?') => output.push(0x7f),
+                Some(b'_') => output.push(b' '),
+                Some(c) => output.push(c),
+                None => output.push(b'\\'),
+            },
+            b'^' => match bytes.peek() {
+                Some(c @ b'@'..=b'~') => {
+                    output.push(c & 0x1f);
+                    bytes.next();
+                }
+                Some(b'?') => {
+                    output.push(0x7f);
+                    bytes.next();
+                }
+                _ => output.push(b'^'),
+            },
+            _ => output.push(b),
+        }
+    }
+
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_plain() {
+        assert_eq!("", unescape(""));
+        assert_eq!("01;34", unescape("01;34"));
+        assert_eq!("\x1b[K", unescape("\x1b[K"));
+    }
+
+    #[test]
+    fn unescape_sequences() {
+        assert_eq!("\x1b[", unescape(r"\e["));
+        assert_eq!("\x1b[0m", unescape(r"\033[0m"));
+        assert_eq!("\x1b[0m", unescape(r"\x1b[0m"));
+        assert_eq!("\x1b[", unescape("^[["));
+        assert_eq!("\x7f \t\\", unescape(r"^?\_\t\\"));
+        assert_eq!("a^", unescape("a^"));
+    }
+}
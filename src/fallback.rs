@@ -0,0 +1,128 @@
+//! Fallbacks for indicators that have no style of their own.
+
+use crate::{Indicator, IndicatorMap};
+
+/// Determines which style is used for an indicator that has no style of its own (see
+/// [`LsColors::style_for_indicator`](crate::LsColors::style_for_indicator)).
+///
+/// Each indicator can fall back to one other indicator, e.g. `ex` (executable file) to `fi`
+/// (regular file). If that has no style either, the style for `no` (normal) is used as a last
+/// resort.
+///
+/// ```
+/// use lscolors::{FallbackPolicy, Indicator, LsColors};
+///
+/// // Don't color executables like regular files
+/// let policy = FallbackPolicy::gnu().with_fallback(Indicator::ExecutableFile, None);
+/// let lscolors = LsColors::from_string("fi=35:ex=0").fallback_policy(policy);
+///
+/// assert!(lscolors.style_for_indicator(Indicator::RegularFile).is_some());
+/// assert!(lscolors.style_for_indicator(Indicator::ExecutableFile).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FallbackPolicy {
+    /// The indicator to use instead, for each indicator.
+    fallbacks: IndicatorMap<Indicator>,
+
+    /// Whether to fall back to `no` as a last resort.
+    normal: bool,
+}
+
+impl FallbackPolicy {
+    /// The fallbacks of GNU `ls`: `su`, `sg`, `ex` and `mh` fall back to `fi`, `tw`, `ow` and
+    /// `st` fall back to `di`, `or` falls back to `ln` and `mi` falls back to `or`. All other
+    /// indicators fall back to `no` directly.
+    ///
+    /// *Note:* Fallbacks are not chained, so `mi` does not fall back to `ln`.
+    pub fn gnu() -> Self {
+        [
+            (Indicator::Setuid, Indicator::RegularFile),
+            (Indicator::Setgid, Indicator::RegularFile),
+            (Indicator::ExecutableFile, Indicator::RegularFile),
+            (Indicator::MultipleHardLinks, Indicator::RegularFile),
+            (Indicator::StickyAndOtherWritable, Indicator::Directory),
+            (Indicator::OtherWritable, Indicator::Directory),
+            (Indicator::Sticky, Indicator::Directory),
+            (Indicator::OrphanedSymbolicLink, Indicator::SymbolicLink),
+            (Indicator::MissingFile, Indicator::OrphanedSymbolicLink),
+        ]
+        .into_iter()
+        .fold(
+            Self::none().with_normal_fallback(true),
+            |policy, (indicator, fallback)| policy.with_fallback(indicator, Some(fallback)),
+        )
+    }
+
+    /// No fallbacks at all: only the style of the indicator itself is used.
+    pub fn none() -> Self {
+        FallbackPolicy {
+            fallbacks: IndicatorMap::default(),
+            normal: false,
+        }
+    }
+
+    /// Set (or remove, if `fallback` is `None`) the fallback for an indicator.
+    pub fn with_fallback(mut self, indicator: Indicator, fallback: Option<Indicator>) -> Self {
+        match fallback {
+            Some(fallback) => self.fallbacks.insert(indicator, fallback),
+            None => self.fallbacks.remove(indicator),
+        }
+        self
+    }
+
+    /// Set whether indicators without a style fall back to `no` as a last resort.
+    pub fn with_normal_fallback(mut self, enabled: bool) -> Self {
+        self.normal = enabled;
+        self
+    }
+
+    /// Get the fallback for an indicator.
+    pub fn fallback_for(&self, indicator: Indicator) -> Option<Indicator> {
+        self.fallbacks.get(indicator).copied()
+    }
+
+    /// Check whether indicators without a style fall back to `no`.
+    pub fn normal_fallback(&self) -> bool {
+        self.normal
+    }
+}
+
+/// The structured representation of [FallbackPolicy] used for (de)serialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FallbackConfig {
+    /// The indicator to use instead, for each indicator.
+    fallbacks: std::collections::BTreeMap<Indicator, Indicator>,
+
+    /// Whether to fall back to `no` as a last resort.
+    normal: bool,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FallbackPolicy {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FallbackConfig {
+            fallbacks: self.fallbacks.iter().map(|(i, f)| (i, *f)).collect(),
+            normal: self.normal,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FallbackPolicy {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = FallbackConfig::deserialize(deserializer)?;
+        Ok(config.fallbacks.into_iter().fold(
+            Self::none().with_normal_fallback(config.normal),
+            |policy, (indicator, fallback)| policy.with_fallback(indicator, Some(fallback)),
+        ))
+    }
+}
+
+impl Default for FallbackPolicy {
+    /// The fallbacks of GNU `ls`, see [`FallbackPolicy::gnu`].
+    fn default() -> Self {
+        Self::gnu()
+    }
+}
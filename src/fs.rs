@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
 use std::fs;
+use std::path::Path;
 
 #[cfg(any(unix, target_os = "redox"))]
 use std::os::unix::fs::MetadataExt;
@@ -22,3 +25,109 @@ pub fn nlink(md: &fs::Metadata) -> u64 {
     #[cfg(not(any(unix, target_os = "redox")))]
     return 1;
 }
+
+/// Check whether the target of a symbolic link is missing. This follows all links, so it also
+/// works for chains of links and for directory symlinks and junctions on Windows (which
+/// `FileType::is_symlink` reports as symbolic links). Other errors, like missing permissions to
+/// access the target, are not treated as a missing target.
+pub fn is_broken_link(path: &Path) -> bool {
+    matches!(path.try_exists(), Ok(false))
+}
+
+/// The Windows `FILE_ATTRIBUTE_HIDDEN` flag.
+pub const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+/// The Windows `FILE_ATTRIBUTE_SYSTEM` flag.
+pub const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+/// Get the Windows file attributes from some metadata if available, otherwise 0.
+#[allow(unused_variables)]
+pub fn file_attributes(md: &fs::Metadata) -> u32 {
+    #[cfg(windows)]
+    return std::os::windows::fs::MetadataExt::file_attributes(md);
+
+    #[cfg(not(windows))]
+    return 0;
+}
+
+/// Check whether a file has capabilities, i.e. a `security.capability` extended attribute. This
+/// is only supported on Linux with the `capabilities` feature, and returns false otherwise.
+#[allow(unused_variables)]
+pub fn has_capabilities(path: &Path) -> bool {
+    #[cfg(all(feature = "capabilities", target_os = "linux"))]
+    return matches!(xattr::get(path, "security.capability"), Ok(Some(_)));
+
+    #[cfg(not(all(feature = "capabilities", target_os = "linux")))]
+    return false;
+}
+
+/// The default value of `PATHEXT` on Windows.
+#[cfg(windows)]
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD;.VBS;.VBE;.JS;.JSE;.WSF;.WSH;.MSC";
+
+/// Check whether a file name ends in one of the `;`-separated `extensions` (like in `PATHEXT`),
+/// ignoring ASCII case.
+pub fn has_extension_in(name: &[u8], extensions: &str) -> bool {
+    extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .any(|ext| {
+            let ext = ext.as_bytes();
+            name.len() > ext.len() && name[name.len() - ext.len()..].eq_ignore_ascii_case(ext)
+        })
+}
+
+/// Check whether a file is executable because of its name. On Windows, this is the case if the
+/// extension is listed in `PATHEXT`, on other platforms this always returns false.
+#[allow(unused_variables)]
+pub fn is_executable_name(name: &OsStr) -> bool {
+    #[cfg(windows)]
+    return has_extension_in(&os_str_bytes(name), pathext());
+
+    #[cfg(not(windows))]
+    return false;
+}
+
+/// Get the value of `PATHEXT`, which is only read once.
+#[cfg(windows)]
+fn pathext() -> &'static str {
+    static PATHEXT: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    PATHEXT.get_or_init(|| std::env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.to_owned()))
+}
+
+/// Get the raw bytes of an `OsStr`, e.g. to write a file name unchanged even if it is not valid
+/// UTF-8. This is lossless on Unix, and falls back to a lossy UTF-8 conversion on other platforms.
+pub fn os_str_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    #[cfg(any(unix, target_os = "redox"))]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Cow::Borrowed(s.as_bytes())
+    }
+
+    #[cfg(not(any(unix, target_os = "redox")))]
+    match s.to_string_lossy() {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_extension_in;
+
+    #[test]
+    fn extensions() {
+        let pathext = ".COM;.EXE;.BAT;;.CMD";
+
+        assert!(has_extension_in(b"setup.exe", pathext));
+        assert!(has_extension_in(b"SETUP.EXE", pathext));
+        assert!(has_extension_in(b"build.Bat", pathext));
+        assert!(has_extension_in(b"a.cmd", pathext));
+
+        assert!(!has_extension_in(b".exe", pathext));
+        assert!(!has_extension_in(b"setup.exe.txt", pathext));
+        assert!(!has_extension_in(b"setupexe", pathext));
+        assert!(!has_extension_in(b"", pathext));
+        assert!(!has_extension_in(b"setup.exe", ""));
+    }
+}
@@ -24,21 +24,33 @@
 //! # }
 //! ```
 
+mod dircolors;
+mod escape;
+mod fallback;
 mod fs;
 pub mod style;
 mod suffix;
+mod terminal;
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
 use std::env;
-use std::ffi::OsString;
-use std::fs::{DirEntry, FileType, Metadata};
+use std::ffi::{OsStr, OsString};
+use std::fs::{DirEntry, File, FileType, Metadata};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::{Arc, OnceLock};
 
+pub use crate::suffix::{MatchedSuffix, SuffixMatch};
 use crate::suffix::{SuffixMap, SuffixMapBuilder};
 
-pub use crate::style::{Color, FontStyle, Style};
+pub use crate::fallback::FallbackPolicy;
+pub use crate::fs::os_str_bytes;
+pub use crate::style::{Color, ColorLevel, FontStyle, Style};
+pub use crate::terminal::{terminal_background, Background};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Indicator {
     /// `no`: Normal (non-filename) text
     Normal,
@@ -111,9 +123,76 @@ pub enum Indicator {
 
     /// `cl`: Code to clear to the end of the line
     ClearLine,
+
+    /// A hidden file or directory (Windows only). There is no `LS_COLORS` code for it, so it
+    /// can only be styled through the API, e.g. with [`LsColors::from_parts`].
+    Hidden,
+
+    /// A system file or directory (Windows only). Like `Hidden`, it has no `LS_COLORS` code.
+    System,
 }
 
 impl Indicator {
+    /// All indicators, in the order of their [index](Indicator::index).
+    const ALL: [Indicator; 26] = [
+        Indicator::Normal,
+        Indicator::RegularFile,
+        Indicator::Directory,
+        Indicator::SymbolicLink,
+        Indicator::FIFO,
+        Indicator::Socket,
+        Indicator::Door,
+        Indicator::BlockDevice,
+        Indicator::CharacterDevice,
+        Indicator::OrphanedSymbolicLink,
+        Indicator::Setuid,
+        Indicator::Setgid,
+        Indicator::Sticky,
+        Indicator::OtherWritable,
+        Indicator::StickyAndOtherWritable,
+        Indicator::ExecutableFile,
+        Indicator::MissingFile,
+        Indicator::Capabilities,
+        Indicator::MultipleHardLinks,
+        Indicator::LeftCode,
+        Indicator::RightCode,
+        Indicator::EndCode,
+        Indicator::Reset,
+        Indicator::ClearLine,
+        Indicator::Hidden,
+        Indicator::System,
+    ];
+
+    /// Get all indicators, in a stable order (file types first, then the literal codes like `lc`,
+    /// and finally the Windows-only `Hidden` and `System`).
+    pub fn all() -> &'static [Indicator] {
+        &Self::ALL
+    }
+
+    /// Whether this indicator holds a literal code (`lc`, `rc`, `ec`, `rs` and `cl`) instead of
+    /// a style.
+    fn is_code(self) -> bool {
+        matches!(
+            self,
+            Indicator::LeftCode
+                | Indicator::RightCode
+                | Indicator::EndCode
+                | Indicator::Reset
+                | Indicator::ClearLine
+        )
+    }
+
+    /// A unique index for each indicator, used to store styles in a fixed-size array.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Like [`from`](#method.from), but matches the indicator code case-insensitively, so `DI`,
+    /// `Di` and `di` all map to `Directory`.
+    pub fn from_ignore_case(indicator: &str) -> Option<Indicator> {
+        Self::from(&indicator.to_ascii_lowercase())
+    }
+
     pub fn from(indicator: &str) -> Option<Indicator> {
         match indicator {
             "no" => Some(Indicator::Normal),
@@ -143,13 +222,161 @@ impl Indicator {
             _ => None,
         }
     }
+
+    /// The two-letter code of this indicator, as used in `LS_COLORS`. This is the inverse of
+    /// [`from`](#method.from). Returns `None` for the Windows-only indicators `Hidden` and
+    /// `System`, which are not supported by GNU `ls`.
+    pub fn code(&self) -> Option<&'static str> {
+        Some(match self {
+            Indicator::Normal => "no",
+            Indicator::RegularFile => "fi",
+            Indicator::Directory => "di",
+            Indicator::SymbolicLink => "ln",
+            Indicator::FIFO => "pi",
+            Indicator::Socket => "so",
+            Indicator::Door => "do",
+            Indicator::BlockDevice => "bd",
+            Indicator::CharacterDevice => "cd",
+            Indicator::OrphanedSymbolicLink => "or",
+            Indicator::Setuid => "su",
+            Indicator::Setgid => "sg",
+            Indicator::Sticky => "st",
+            Indicator::OtherWritable => "ow",
+            Indicator::StickyAndOtherWritable => "tw",
+            Indicator::ExecutableFile => "ex",
+            Indicator::MissingFile => "mi",
+            Indicator::Capabilities => "ca",
+            Indicator::MultipleHardLinks => "mh",
+            Indicator::LeftCode => "lc",
+            Indicator::RightCode => "rc",
+            Indicator::EndCode => "ec",
+            Indicator::Reset => "rs",
+            Indicator::ClearLine => "cl",
+            Indicator::Hidden | Indicator::System => return None,
+        })
+    }
+
+    /// The name of this indicator when (de)serialized: its code, or `hidden` and `system` for the
+    /// indicators without a code.
+    #[cfg(feature = "serde")]
+    fn serde_name(self) -> &'static str {
+        match self {
+            Indicator::Hidden => "hidden",
+            Indicator::System => "system",
+            _ => self.code().unwrap_or_default(),
+        }
+    }
+}
+
+/// Maps indicators to their styles (or literal codes).
+#[derive(Debug, Clone)]
+struct IndicatorMap<T = Style> {
+    /// Values indexed by [Indicator::index].
+    values: [Option<T>; Indicator::ALL.len()],
+}
+
+impl<T> Default for IndicatorMap<T> {
+    fn default() -> Self {
+        IndicatorMap {
+            values: std::array::from_fn(|_| None),
+        }
+    }
+}
+
+impl<T> IndicatorMap<T> {
+    fn get(&self, indicator: Indicator) -> Option<&T> {
+        self.values[indicator.index()].as_ref()
+    }
+
+    fn contains_key(&self, indicator: Indicator) -> bool {
+        self.values[indicator.index()].is_some()
+    }
+
+    fn insert(&mut self, indicator: Indicator, value: T) {
+        self.values[indicator.index()] = Some(value);
+    }
+
+    fn remove(&mut self, indicator: Indicator) {
+        self.values[indicator.index()] = None;
+    }
+
+    /// Get mutable references to all values.
+    fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.values.iter_mut().flatten()
+    }
+
+    /// Iterate over all indicators that have a value.
+    #[cfg(feature = "serde")]
+    fn iter(&self) -> impl Iterator<Item = (Indicator, &T)> {
+        Indicator::ALL
+            .iter()
+            .zip(self.values.iter())
+            .filter_map(|(indicator, value)| Some((*indicator, value.as_ref()?)))
+    }
+}
+
+/// Indicators are (de)serialized as their two-letter codes (`di`, `ln`, ...), or as `hidden` and
+/// `system`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Indicator {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.serde_name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Indicator {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Indicator::ALL
+            .iter()
+            .copied()
+            .find(|indicator| indicator.serde_name() == code)
+            .ok_or_else(|| serde::de::Error::custom(format_args!("unknown indicator `{}`", code)))
+    }
+}
+
+/// A single rule of an [LsColors] configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A style for an indicator, like `di=01;34`
+    Indicator(Indicator),
+
+    /// A style for a filename suffix, like `*.gz=01;31`
+    Suffix(String),
+}
+
+/// A color which exceeds the capabilities of a terminal, as reported by
+/// [`LsColors::validate_for_level`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Problem {
+    /// The rule that uses the color
+    pub rule: Rule,
+    /// The unsupported color
+    pub color: Color,
+}
+
+/// Explains which rule determines the style of a file, as returned by
+/// [`LsColors::explain_for_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The indicator for the type of the file
+    pub indicator: Indicator,
+    /// The suffix rule that matches the file name (without the leading `*`, like [Rule::Suffix])
+    /// and its style, if any. Suffix rules are only used for regular files.
+    pub suffix: Option<(String, Option<Style>)>,
+    /// The resulting style, as returned by [`LsColors::style_for_path`]
+    pub style: Option<Style>,
 }
 
 /// Iterator over the path components with their respective style.
-pub struct StyledComponents<'a> {
+pub struct StyledComponents<'a, F = fn(&Path) -> Option<Metadata>> {
     /// Reference to the underlying LsColors object
     lscolors: &'a LsColors,
 
+    /// Provides the metadata for each component
+    metadata: F,
+
     /// Full path to the current component
     component_path: PathBuf,
 
@@ -157,31 +384,78 @@ pub struct StyledComponents<'a> {
     components: std::iter::Peekable<std::path::Components<'a>>,
 }
 
-impl<'a> Iterator for StyledComponents<'a> {
+impl<'a, F> StyledComponents<'a, F>
+where
+    F: FnMut(&Path) -> Option<Metadata>,
+{
+    /// Turn this into an iterator that borrows the components from the path whenever no
+    /// separator needs to be appended (i.e. for the last component, the root and prefixes).
+    pub fn borrowed(self) -> BorrowedStyledComponents<'a, F> {
+        BorrowedStyledComponents { inner: self }
+    }
+
+    /// Get the next component, whether it needs a trailing separator, and its style.
+    fn next_component(&mut self) -> Option<(&'a OsStr, bool, Option<&'a Style>)> {
+        let component = self.components.next()?;
+
+        self.component_path.push(component);
+        let metadata = (self.metadata)(&self.component_path);
+        let style = self
+            .lscolors
+            .style_for_path_with_metadata(&self.component_path, metadata.as_ref());
+
+        let separator = self.components.peek().is_some()
+            && match component {
+                // Prefix needs no separator, as it is always followed by RootDir.
+                // RootDir is already a separator.
+                Component::Prefix(_) | Component::RootDir => false,
+                // Everything else uses a separator that is painted the same way as the component.
+                Component::CurDir | Component::ParentDir | Component::Normal(_) => true,
+            };
+
+        Some((component.as_os_str(), separator, style))
+    }
+}
+
+impl<'a, F> Iterator for StyledComponents<'a, F>
+where
+    F: FnMut(&Path) -> Option<Metadata>,
+{
     type Item = (OsString, Option<&'a Style>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(component) = self.components.next() {
-            let mut component_str = component.as_os_str().to_os_string();
-
-            self.component_path.push(&component_str);
-            let style = self.lscolors.style_for_path(&self.component_path);
-
-            if self.components.peek().is_some() {
-                match component {
-                    // Prefix needs no separator, as it is always followed by RootDir.
-                    // RootDir is already a separator.
-                    Component::Prefix(_) | Component::RootDir => {}
-                    // Everything else uses a separator that is painted the same way as the component.
-                    Component::CurDir | Component::ParentDir | Component::Normal(_) => {
-                        component_str.push(MAIN_SEPARATOR.to_string());
-                    }
-                }
-            }
+        let (component, separator, style) = self.next_component()?;
+
+        let mut component_str = component.to_os_string();
+        if separator {
+            component_str.push(MAIN_SEPARATOR.to_string());
+        }
+
+        Some((component_str, style))
+    }
+}
+
+/// Iterator over the path components with their respective style, which only allocates for
+/// components that need a trailing separator. See [StyledComponents::borrowed].
+pub struct BorrowedStyledComponents<'a, F = fn(&Path) -> Option<Metadata>> {
+    inner: StyledComponents<'a, F>,
+}
+
+impl<'a, F> Iterator for BorrowedStyledComponents<'a, F>
+where
+    F: FnMut(&Path) -> Option<Metadata>,
+{
+    type Item = (Cow<'a, OsStr>, Option<&'a Style>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (component, separator, style) = self.inner.next_component()?;
 
-            Some((component_str, style))
+        if separator {
+            let mut component_str = component.to_os_string();
+            component_str.push(MAIN_SEPARATOR.to_string());
+            Some((Cow::Owned(component_str), style))
         } else {
-            None
+            Some((Cow::Borrowed(component), style))
         }
     }
 }
@@ -199,6 +473,13 @@ pub trait Colorable {
 
     /// Try to get the metadata for this file.
     fn metadata(&self) -> Option<Metadata>;
+
+    /// Check whether the target of this file exists, if it is a symbolic link. This can be
+    /// implemented to avoid an extra `stat` call if the status is already known. Returning
+    /// `None` (the default) means that the file system will be checked.
+    fn symlink_target_exists(&self) -> Option<bool> {
+        None
+    }
 }
 
 impl Colorable for DirEntry {
@@ -219,13 +500,120 @@ impl Colorable for DirEntry {
     }
 }
 
+#[cfg(feature = "walkdir")]
+impl Colorable for walkdir::DirEntry {
+    fn path(&self) -> PathBuf {
+        self.path().to_owned()
+    }
+
+    fn file_name(&self) -> OsString {
+        self.file_name().to_owned()
+    }
+
+    fn file_type(&self) -> Option<FileType> {
+        Some(self.file_type())
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        self.metadata().ok()
+    }
+}
+
+#[cfg(feature = "ignore")]
+impl Colorable for ignore::DirEntry {
+    fn path(&self) -> PathBuf {
+        self.path().to_owned()
+    }
+
+    fn file_name(&self) -> OsString {
+        self.file_name().to_owned()
+    }
+
+    fn file_type(&self) -> Option<FileType> {
+        self.file_type()
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        self.metadata().ok()
+    }
+}
+
+/// A path with its (optional) metadata.
+struct PathWithMetadata<'a> {
+    path: &'a Path,
+    metadata: Option<&'a Metadata>,
+}
+
+impl Colorable for PathWithMetadata<'_> {
+    fn path(&self) -> PathBuf {
+        self.path.to_owned()
+    }
+
+    fn file_name(&self) -> OsString {
+        last_component(self.path)
+    }
+
+    fn file_type(&self) -> Option<FileType> {
+        self.metadata.map(|m| m.file_type())
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        self.metadata.cloned()
+    }
+}
+
+/// A path with its (optional) file type, but without metadata.
+struct PathWithFileType<'a> {
+    path: &'a Path,
+    file_type: Option<FileType>,
+}
+
+impl Colorable for PathWithFileType<'_> {
+    fn path(&self) -> PathBuf {
+        self.path.to_owned()
+    }
+
+    fn file_name(&self) -> OsString {
+        last_component(self.path)
+    }
+
+    fn file_type(&self) -> Option<FileType> {
+        self.file_type
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        None
+    }
+}
+
+/// Get the last component of a path.
+fn last_component(path: &Path) -> OsString {
+    // Path::file_name() only works if the last component is Normal, but
+    // we want it for all component types, so we open code it
+
+    path.components()
+        .next_back()
+        .map(|c| c.as_os_str())
+        .unwrap_or_else(|| path.as_os_str())
+        .to_owned()
+}
+
 /// Builder for [LsColors].
 struct LsColorsBuilder {
-    indicator_mapping: HashMap<Indicator, Style>,
+    indicator_mapping: IndicatorMap,
 
-    /// Whether Indicator::RegularFile falls back to Indicator::Normal
-    /// (see <https://github.com/sharkdp/lscolors/issues/48#issuecomment-1582830387>)
-    file_normal_fallback: bool,
+    /// Literal values of the code indicators (`lc`, `rc`, ...)
+    code_mapping: IndicatorMap<String>,
+
+    /// Indicators that were explicitly reset (`di=` or `di=0`), which don't fall back to
+    /// Indicator::Normal (see <https://github.com/sharkdp/lscolors/issues/48#issuecomment-1582830387>)
+    reset_indicators: IndicatorMap<()>,
+
+    /// Whether indicator codes are matched case-insensitively
+    ignore_indicator_case: bool,
+
+    /// Whether symbolic links are styled like their targets (`ln=target`)
+    symlink_as_target: bool,
 
     suffixes: SuffixMapBuilder,
 }
@@ -233,43 +621,135 @@ struct LsColorsBuilder {
 impl LsColorsBuilder {
     fn empty() -> Self {
         Self {
-            indicator_mapping: HashMap::new(),
-            file_normal_fallback: true,
+            indicator_mapping: IndicatorMap::default(),
+            code_mapping: IndicatorMap::default(),
+            reset_indicators: IndicatorMap::default(),
+            ignore_indicator_case: false,
+            symlink_as_target: false,
             suffixes: SuffixMapBuilder::default(),
         }
     }
 
+    /// Add all entries of an `LS_COLORS`-style string. An empty value (`di=`) is treated the same
+    /// way as `di=0` or `di=00`, just like GNU `ls` does: the entry is reset to "no color", i.e. it
+    /// overrides the default style instead of being ignored, and it doesn't fall back to `no`.
     fn add_from_string(&mut self, input: &str) {
-        for entry in input.split(':') {
+        for entry in input.split([':', '\n']) {
+            let entry = entry.trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+
             let parts: Vec<_> = entry.split('=').collect();
 
             if let Some([entry, ansi_style]) = parts.get(0..2) {
-                let style = Style::from_ansi_sequence(ansi_style);
-                if let Some(suffix) = entry.strip_prefix('*') {
-                    self.suffixes.push(suffix, style);
-                } else if let Some(indicator) = Indicator::from(entry) {
-                    if let Some(style) = style {
-                        self.indicator_mapping.insert(indicator, style);
-                    } else {
-                        self.indicator_mapping.remove(&indicator);
-                        if indicator == Indicator::RegularFile {
-                            self.file_normal_fallback = false;
-                        }
-                    }
+                self.add_entry(entry.trim(), ansi_style.trim());
+            }
+        }
+    }
+
+    /// Add all entries of a `dircolors` database (see [crate::dircolors]) that apply to the given
+    /// terminal.
+    fn add_from_dircolors<R: BufRead>(
+        &mut self,
+        reader: R,
+        term: &str,
+        colorterm: &str,
+    ) -> io::Result<()> {
+        let mut filter = crate::dircolors::TermFilter::new(term, colorterm);
+        for line in reader.lines() {
+            let line = line?;
+            if let Some((keyword, value)) = crate::dircolors::parse_line(&line) {
+                if !filter.apply(keyword, value) {
+                    continue;
+                }
+                if let Some(entry) = crate::dircolors::ls_colors_key(keyword) {
+                    self.add_entry(&entry, value);
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Add a single entry like `di` / `01;34` or `*.gz` / `01;31`.
+    fn add_entry(&mut self, entry: &str, value: &str) {
+        if let Some(suffix) = entry.strip_prefix('*') {
+            self.suffixes.push(suffix, Style::from_ansi_sequence(value));
+        } else if let Some(indicator) = if self.ignore_indicator_case {
+            Indicator::from_ignore_case(entry)
+        } else {
+            Indicator::from(entry)
+        } {
+            if indicator.is_code() {
+                self.code_mapping
+                    .insert(indicator, crate::escape::unescape(value));
+            } else if indicator == Indicator::SymbolicLink && value == "target" {
+                self.symlink_as_target = true;
+                self.indicator_mapping.remove(indicator);
+            } else {
+                if indicator == Indicator::SymbolicLink {
+                    self.symlink_as_target = false;
+                }
+                self.set_indicator(indicator, Style::from_ansi_sequence(value));
+            }
+        }
+    }
+
+    /// Set (or reset, if `style` is `None`) the style for an indicator. Code indicators (like
+    /// `lc`) hold literal codes instead of styles, so they are ignored.
+    fn set_indicator(&mut self, indicator: Indicator, style: Option<Style>) {
+        set_indicator_style(
+            &mut self.indicator_mapping,
+            &mut self.reset_indicators,
+            indicator,
+            style,
+        );
     }
 
     fn build(self) -> LsColors {
         LsColors {
-            indicator_mapping: self.indicator_mapping,
-            file_normal_fallback: self.file_normal_fallback,
-            suffixes: self.suffixes.build(),
+            indicator_mapping: Arc::new(self.indicator_mapping),
+            code_mapping: Arc::new(self.code_mapping),
+            reset_indicators: self.reset_indicators,
+            symlink_as_target: self.symlink_as_target,
+            executable_extensions: None,
+            fallback: FallbackPolicy::gnu(),
+            suffixes: Arc::new(self.suffixes.build()),
         }
     }
 }
 
+/// Get the path and the metadata of the target of a symbolic link, or `None` if it is missing.
+fn symlink_target(path: &Path) -> Option<(PathBuf, Metadata)> {
+    let target = path.read_link().ok().map(|target| match path.parent() {
+        Some(parent) => parent.join(target),
+        None => target,
+    });
+
+    Some((target?, path.metadata().ok()?))
+}
+
+/// Set (or reset) the style for an indicator, see [LsColorsBuilder::set_indicator]. This is shared
+/// with `Extend`, which modifies an [LsColors] instance in place.
+fn set_indicator_style(
+    indicator_mapping: &mut IndicatorMap,
+    reset_indicators: &mut IndicatorMap<()>,
+    indicator: Indicator,
+    style: Option<Style>,
+) {
+    if indicator.is_code() {
+        return;
+    }
+
+    if let Some(style) = style {
+        indicator_mapping.insert(indicator, style);
+        reset_indicators.remove(indicator);
+    } else {
+        indicator_mapping.remove(indicator);
+        reset_indicators.insert(indicator, ());
+    }
+}
+
 const LS_COLORS_DEFAULT: &str = "rs=0:lc=\x1b[:rc=m:cl=\x1b[K:ex=01;32:sg=30;43:su=37;41:di=01;34:st=37;44:ow=34;42:tw=30;42:ln=01;36:bd=01;33:cd=01;33:do=01;35:pi=33:so=01;35:";
 
 impl Default for LsColorsBuilder {
@@ -281,15 +761,33 @@ impl Default for LsColorsBuilder {
 }
 
 /// Holds information about how different file system entries should be colorized / styled.
+///
+/// With the `serde` feature enabled, an `LsColors` instance can be (de)serialized as a map of
+/// indicator codes to styles, together with the ordered list of suffix rules.
+///
+/// Cloning is cheap, since the styles and the suffix matcher are shared between clones (they are
+/// only copied if one of the clones is modified).
 #[derive(Debug, Clone)]
 pub struct LsColors {
-    indicator_mapping: HashMap<Indicator, Style>,
+    indicator_mapping: Arc<IndicatorMap>,
+
+    /// Literal values of the code indicators (`lc`, `rc`, ...)
+    code_mapping: Arc<IndicatorMap<String>>,
+
+    /// Indicators that were explicitly reset (`di=` or `di=0`), which don't fall back to
+    /// Indicator::Normal (see <https://github.com/sharkdp/lscolors/issues/48#issuecomment-1582830387>)
+    reset_indicators: IndicatorMap<()>,
 
-    /// Whether Indicator::RegularFile falls back to Indicator::Normal
-    /// (see <https://github.com/sharkdp/lscolors/issues/48#issuecomment-1582830387>)
-    file_normal_fallback: bool,
+    /// Whether symbolic links are styled like their targets (`ln=target`)
+    symlink_as_target: bool,
 
-    suffixes: SuffixMap,
+    /// `;`-separated extensions of executable files, for files without metadata
+    executable_extensions: Option<Arc<str>>,
+
+    /// Which styles to use for indicators without a style
+    fallback: FallbackPolicy,
+
+    suffixes: Arc<SuffixMap>,
 }
 
 impl Default for LsColors {
@@ -300,29 +798,246 @@ impl Default for LsColors {
     }
 }
 
+/// Collects indicator styles into an [LsColors] instance. Like [LsColors::empty], this starts
+/// without any pre-defined styles. Code indicators (like `lc`) are ignored.
+impl FromIterator<(Indicator, Style)> for LsColors {
+    fn from_iter<I: IntoIterator<Item = (Indicator, Style)>>(iter: I) -> Self {
+        let mut builder = LsColorsBuilder::empty();
+        for (indicator, style) in iter {
+            builder.set_indicator(indicator, Some(style));
+        }
+        builder.build()
+    }
+}
+
+/// Adds (or overrides) indicator styles. Code indicators (like `lc`) are ignored.
+impl Extend<(Indicator, Style)> for LsColors {
+    fn extend<I: IntoIterator<Item = (Indicator, Style)>>(&mut self, iter: I) {
+        let indicator_mapping = Arc::make_mut(&mut self.indicator_mapping);
+        for (indicator, style) in iter {
+            set_indicator_style(
+                indicator_mapping,
+                &mut self.reset_indicators,
+                indicator,
+                Some(style),
+            );
+        }
+    }
+}
+
 impl LsColors {
     /// Construct an empty [`LsColors`](struct.LsColors.html) instance with no pre-defined styles.
     pub fn empty() -> Self {
         LsColorsBuilder::empty().build()
     }
 
+    /// Construct an [`LsColors`](struct.LsColors.html) instance from indicator styles and suffix
+    /// styles, without any pre-defined styles. Suffixes are given without the leading `*` (e.g.
+    /// `".gz"`), and later suffixes take precedence over earlier ones, just like in
+    /// [`from_string`](#method.from_string). A suffix with a `None` style disables styling for
+    /// matching files. Code indicators (like `lc`) hold literal codes instead of styles, so they
+    /// are ignored.
+    pub fn from_parts<I, S>(indicators: I, suffixes: S) -> Self
+    where
+        I: IntoIterator<Item = (Indicator, Style)>,
+        S: IntoIterator<Item = (String, Option<Style>)>,
+    {
+        let mut builder = LsColorsBuilder::empty();
+        for (indicator, style) in indicators {
+            builder.set_indicator(indicator, Some(style));
+        }
+        for (suffix, style) in suffixes {
+            builder.suffixes.push(suffix, style);
+        }
+        builder.build()
+    }
+
+    /// Match all suffixes case-sensitively, so `*.jpg` no longer matches `image.JPG`. By default
+    /// (like in GNU `ls`), suffixes are matched case-insensitively, unless the same suffix is
+    /// given with different capitalizations and different styles.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    ///
+    /// let lscolors = LsColors::from_string("*.jpg=35").case_sensitive_suffixes(true);
+    /// assert!(lscolors.style_for_str("image.jpg").is_some());
+    /// assert!(lscolors.style_for_str("image.JPG").is_none());
+    /// ```
+    pub fn case_sensitive_suffixes(mut self, case_sensitive: bool) -> Self {
+        let mut builder = self.suffixes.to_builder();
+        builder.case_sensitive(case_sensitive);
+        self.suffixes = Arc::new(builder.build());
+        self
+    }
+
+    /// Use a different [`FallbackPolicy`] for indicators without a style. By default, the same
+    /// fallbacks as in GNU `ls` are used ([`FallbackPolicy::gnu`]).
+    pub fn fallback_policy(mut self, policy: FallbackPolicy) -> Self {
+        self.fallback = policy;
+        self
+    }
+
+    /// Match suffixes case-insensitively with the Unicode lowercase mapping, so `*.DatENsätze`
+    /// also matches `.datensätze`. By default, only ASCII letters are matched case-insensitively.
+    ///
+    /// *Note:* This is slower, since each file name has to be lowercased (on the heap) first.
+    pub fn unicode_case_insensitive_suffixes(mut self, unicode: bool) -> Self {
+        let mut builder = self.suffixes.to_builder();
+        builder.unicode_case_insensitive(unicode);
+        self.suffixes = Arc::new(builder.build());
+        self
+    }
+
+    /// Choose how to pick between multiple matching suffixes. By default, the suffix that was
+    /// declared last wins (like in GNU `ls`).
+    ///
+    /// ```
+    /// use lscolors::{Color, LsColors, SuffixMatch};
+    ///
+    /// let lscolors = LsColors::from_string("*.tar.gz=31:*.gz=32");
+    /// let style = lscolors.style_for_str("archive.tar.gz").unwrap();
+    /// assert_eq!(Some(Color::Green), style.foreground);
+    ///
+    /// let lscolors = lscolors.suffix_match(SuffixMatch::Longest);
+    /// let style = lscolors.style_for_str("archive.tar.gz").unwrap();
+    /// assert_eq!(Some(Color::Red), style.foreground);
+    /// ```
+    pub fn suffix_match(mut self, match_mode: SuffixMatch) -> Self {
+        let mut builder = self.suffixes.to_builder();
+        builder.match_mode(match_mode);
+        self.suffixes = Arc::new(builder.build());
+        self
+    }
+
+    /// Style files with one of the given extensions (like `sh` or `.py`) as executables (`ex`)
+    /// if their metadata is not available, e.g. for paths that don't exist locally. Suffix rules
+    /// take precedence, and an empty list turns this off again (the default).
+    ///
+    /// ```
+    /// use lscolors::{Indicator, LsColors};
+    ///
+    /// let lscolors = LsColors::default().executable_extensions(&["sh", "py"]);
+    /// assert_eq!(
+    ///     lscolors.style_for_indicator(Indicator::ExecutableFile),
+    ///     lscolors.style_for_path("does/not/exist/run.sh")
+    /// );
+    /// ```
+    pub fn executable_extensions(mut self, extensions: &[&str]) -> Self {
+        self.executable_extensions = (!extensions.is_empty()).then(|| {
+            extensions
+                .iter()
+                .map(|ext| format!(".{}", ext.trim_start_matches('.')))
+                .collect::<Vec<_>>()
+                .join(";")
+                .into()
+        });
+        self
+    }
+
+    /// Get a process-wide [`LsColors`](struct.LsColors.html) instance, which is created from the
+    /// `LS_COLORS` environment variable (or the default style, if it is not set) on first use.
+    ///
+    /// *Note:* The environment is only read once, later changes to `LS_COLORS` are not taken into
+    /// account.
+    pub fn global() -> &'static LsColors {
+        static GLOBAL: OnceLock<LsColors> = OnceLock::new();
+        GLOBAL.get_or_init(|| Self::from_env().unwrap_or_default())
+    }
+
     /// Creates a new [`LsColors`](struct.LsColors.html) instance from the `LS_COLORS` environment
     /// variable. The basis for this is a default style as constructed via the `Default`
     /// implementation.
     pub fn from_env() -> Option<Self> {
-        env::var("LS_COLORS")
-            .ok()
-            .as_ref()
-            .map(|s| Self::from_string(s))
+        Self::from_env_var("LS_COLORS")
+    }
+
+    /// Like [`from_env`](#method.from_env), but reads the given environment variable instead of
+    /// `LS_COLORS`, e.g. for tools with their own configuration. Returns `None` if the variable
+    /// is not set (or not valid Unicode).
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    ///
+    /// let lscolors = LsColors::from_env_var("MYTOOL_COLORS")
+    ///     .or_else(LsColors::from_env)
+    ///     .unwrap_or_default();
+    /// ```
+    pub fn from_env_var(name: &str) -> Option<Self> {
+        env::var(name).ok().as_ref().map(|s| Self::from_string(s))
+    }
+
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from the output of
+    /// `dircolors -b` (Bourne shell) or `dircolors -c` (C shell), e.g. if it was saved to a file.
+    /// Returns `None` if the output does not assign `LS_COLORS`.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    ///
+    /// let output = "LS_COLORS='di=01;34:*.tar=01;31:';\nexport LS_COLORS\n";
+    /// let lscolors = LsColors::from_dircolors_output(output).unwrap();
+    /// assert!(lscolors.style_for_str("archive.tar").is_some());
+    /// ```
+    pub fn from_dircolors_output(output: &str) -> Option<Self> {
+        crate::dircolors::parse_shell_assignment(output).map(|s| Self::from_string(&s))
     }
 
     /// Creates a new [`LsColors`](struct.LsColors.html) instance from the given string.
+    ///
+    /// Entries may also be separated by newlines. Whitespace around keys and values, empty
+    /// entries and entries that start with `#` are ignored.
     pub fn from_string(input: &str) -> Self {
         let mut builder = LsColorsBuilder::default();
         builder.add_from_string(input);
         builder.build()
     }
 
+    /// Like [`from_string`](#method.from_string), but indicator codes are matched
+    /// case-insensitively, so `DI=01;34` is accepted as well. GNU `ls` only accepts lowercase
+    /// indicator codes and ignores all others.
+    pub fn from_string_lenient(input: &str) -> Self {
+        let mut builder = LsColorsBuilder {
+            ignore_indicator_case: true,
+            ..LsColorsBuilder::default()
+        };
+        builder.add_from_string(input);
+        builder.build()
+    }
+
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from a `dircolors` database, as
+    /// found in `~/.dircolors` or `/etc/DIR_COLORS`. Like for
+    /// [`from_string`](#method.from_string), the basis for this is the default style.
+    ///
+    /// Lines like `DIR 01;34`, `.tar 01;31` or `*README 01;33` are supported, as well as blank
+    /// lines and `#` comments. Like `dircolors`, rules in `TERM` / `COLORTERM` blocks only apply
+    /// if the pattern matches the `TERM` / `COLORTERM` environment variables (see
+    /// [`from_dircolors_for_term`](#method.from_dircolors_for_term)). Other keywords that do not
+    /// describe a style (like `COLOR` or `OPTIONS`) are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let term = env::var("TERM").unwrap_or_else(|_| "none".into());
+        Self::from_dircolors_for_term(reader, &term)
+    }
+
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from a `dircolors` database,
+    /// using only the rules that apply to the given terminal name.
+    ///
+    /// Rules before the first `TERM` line apply to all terminals. One or more consecutive `TERM`
+    /// lines with shell-style patterns (like `TERM xterm*`) start a block of rules that only
+    /// applies if any of the patterns matches `term`. `COLORTERM` lines work the same way, but
+    /// are matched against the `COLORTERM` environment variable.
+    pub fn from_dircolors_for_term<R: BufRead>(reader: R, term: &str) -> io::Result<Self> {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+
+        let mut builder = LsColorsBuilder::default();
+        builder.add_from_dircolors(reader, term, &colorterm)?;
+        Ok(builder.build())
+    }
+
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from a `dircolors` database
+    /// file. See [`from_reader`](#method.from_reader).
+    pub fn from_dircolors_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
     /// Get the ANSI style for a given path.
     ///
     /// *Note:* this function calls `Path::symlink_metadata` internally. If you already happen to
@@ -332,19 +1047,64 @@ impl LsColors {
         self.style_for_path_with_metadata(path, metadata.as_ref())
     }
 
+    /// Like [`style_for_path`](#method.style_for_path), but if there are no styles besides `fi`
+    /// and the suffix rules, the metadata is not queried at all and every path is styled like a
+    /// regular file. This is faster for pure suffix-based coloring, but unlike `ls`, it also
+    /// applies the suffix rules and `fi` to directories, symbolic links and other special files.
+    ///
+    /// ```
+    /// use lscolors::{Color, LsColors, Style};
+    ///
+    /// let rs = Style::from_ansi_sequence("31").unwrap();
+    /// let lscolors = LsColors::from_parts([], vec![(".rs".into(), Some(rs))]);
+    /// let style = lscolors.style_for_path_fast("src/main.rs").unwrap();
+    /// assert_eq!(Some(Color::Red), style.foreground);
+    /// ```
+    pub fn style_for_path_fast<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
+        if self.needs_file_type() {
+            self.style_for_path(path)
+        } else {
+            self.style_for_path_with_metadata(path, None)
+        }
+    }
+
     /// Check if an indicator has an associated color.
     fn has_color_for(&self, indicator: Indicator) -> bool {
-        self.indicator_mapping.contains_key(&indicator)
+        self.indicator_mapping.contains_key(indicator)
+    }
+
+    /// Check if we need the file type at all, i.e. if there is a style for any indicator other
+    /// than `fi`, or symbolic links are styled like their targets.
+    fn needs_file_type(&self) -> bool {
+        self.symlink_as_target
+            || Indicator::ALL.iter().any(|&indicator| {
+                indicator != Indicator::RegularFile
+                    && !indicator.is_code()
+                    && self.has_color_for(indicator)
+            })
     }
 
     /// Check if we need metadata to color a regular file.
     fn needs_file_metadata(&self) -> bool {
         self.has_color_for(Indicator::Setuid)
             || self.has_color_for(Indicator::Setgid)
+            || self.needs_capabilities()
             || self.has_color_for(Indicator::ExecutableFile)
             || self.has_color_for(Indicator::MultipleHardLinks)
     }
 
+    /// Check if we need to look up file capabilities to color a regular file.
+    fn needs_capabilities(&self) -> bool {
+        cfg!(all(feature = "capabilities", target_os = "linux"))
+            && self.has_color_for(Indicator::Capabilities)
+    }
+
+    /// Check if we need to look up the Windows file attributes (hidden, system).
+    fn needs_file_attributes(&self) -> bool {
+        cfg!(windows)
+            && (self.has_color_for(Indicator::Hidden) || self.has_color_for(Indicator::System))
+    }
+
     /// Check if we need metadata to color a directory.
     fn needs_dir_metadata(&self) -> bool {
         self.has_color_for(Indicator::StickyAndOtherWritable)
@@ -352,11 +1112,45 @@ impl LsColors {
             || self.has_color_for(Indicator::Sticky)
     }
 
+    /// Check whether styling a file of the given type requires its full `Metadata` (e.g. for the
+    /// permission bits), or whether the file type alone is enough. This can be used to skip a
+    /// `stat` call before [`style_for_path_with_metadata`](#method.style_for_path_with_metadata).
+    ///
+    /// If the file type is unknown, metadata is always needed.
+    pub fn needs_metadata_for(&self, file_type: Option<FileType>) -> bool {
+        match file_type {
+            Some(file_type) if file_type.is_file() => {
+                self.needs_file_metadata() || self.needs_file_attributes()
+            }
+            Some(file_type) if file_type.is_dir() => {
+                self.needs_dir_metadata() || self.needs_file_attributes()
+            }
+            Some(_) => false,
+            None => true,
+        }
+    }
+
     /// Get the indicator type for a path with corresponding metadata.
     fn indicator_for<F: Colorable>(&self, file: &F) -> Indicator {
         let file_type = file.file_type();
 
         if let Some(file_type) = file_type {
+            if (file_type.is_file() || file_type.is_dir()) && self.needs_file_attributes() {
+                if let Some(metadata) = file.metadata() {
+                    let attributes = crate::fs::file_attributes(&metadata);
+
+                    if self.has_color_for(Indicator::System)
+                        && attributes & crate::fs::FILE_ATTRIBUTE_SYSTEM != 0
+                    {
+                        return Indicator::System;
+                    } else if self.has_color_for(Indicator::Hidden)
+                        && attributes & crate::fs::FILE_ATTRIBUTE_HIDDEN != 0
+                    {
+                        return Indicator::Hidden;
+                    }
+                }
+            }
+
             if file_type.is_file() {
                 if self.needs_file_metadata() {
                     if let Some(metadata) = file.metadata() {
@@ -367,13 +1161,19 @@ impl LsColors {
                             return Indicator::Setuid;
                         } else if self.has_color_for(Indicator::Setgid) && mode & 0o2000 != 0 {
                             return Indicator::Setgid;
+                        } else if self.needs_capabilities()
+                            && crate::fs::has_capabilities(&file.path())
+                        {
+                            return Indicator::Capabilities;
                         } else if self.has_color_for(Indicator::ExecutableFile)
-                            && mode & 0o0111 != 0
+                            && (mode & 0o0111 != 0 || self.is_executable_name(&file.file_name()))
                         {
                             return Indicator::ExecutableFile;
                         } else if self.has_color_for(Indicator::MultipleHardLinks) && nlink > 1 {
                             return Indicator::MultipleHardLinks;
                         }
+                    } else if self.is_executable_by_extension(&file.file_name()) {
+                        return Indicator::ExecutableFile;
                     }
                 }
 
@@ -398,8 +1198,11 @@ impl LsColors {
 
                 Indicator::Directory
             } else if file_type.is_symlink() {
-                // This works because `Path::exists` traverses symlinks.
-                if self.has_color_for(Indicator::OrphanedSymbolicLink) && !file.path().exists() {
+                if self.has_color_for(Indicator::OrphanedSymbolicLink)
+                    && file
+                        .symlink_target_exists()
+                        .map_or_else(|| crate::fs::is_broken_link(&file.path()), |exists| !exists)
+                {
                     return Indicator::OrphanedSymbolicLink;
                 }
 
@@ -426,21 +1229,60 @@ impl LsColors {
                 // Treat files of unknown type as errors
                 Indicator::MissingFile
             }
+        } else if self.is_executable_by_extension(&file.file_name()) {
+            Indicator::ExecutableFile
         } else {
             // Default to a regular file, so we still try the suffix map when no metadata is available
             Indicator::RegularFile
         }
     }
 
-    /// Get the ANSI style for a colorable path.
-    pub fn style_for<F: Colorable>(&self, file: &F) -> Option<&Style> {
-        let indicator = self.indicator_for(file);
+    /// Check whether a file is executable because of its name (see
+    /// [`crate::fs::is_executable_name`]). Suffix rules take precedence
+    /// over executables by extension.
+    fn is_executable_name(&self, file_name: &OsStr) -> bool {
+        crate::fs::is_executable_name(file_name)
+            && self
+                .suffixes
+                .get(crate::fs::os_str_bytes(file_name))
+                .is_none()
+    }
 
-        if indicator == Indicator::RegularFile {
-            // Note: using '.to_str()' here means that filename
-            // matching will not work with invalid-UTF-8 paths.
-            let filename = file.file_name();
-            if let Some(style) = self.style_for_str(filename.to_str()?) {
+    /// Check whether a file without metadata is executable because of its extension (see
+    /// [`executable_extensions`](#method.executable_extensions)).
+    fn is_executable_by_extension(&self, file_name: &OsStr) -> bool {
+        let Some(extensions) = &self.executable_extensions else {
+            return false;
+        };
+
+        // Suffix rules take precedence over executables by extension
+        let file_name = crate::fs::os_str_bytes(file_name);
+        self.has_color_for(Indicator::ExecutableFile)
+            && crate::fs::has_extension_in(&file_name, extensions)
+            && self.suffixes.get(&file_name).is_none()
+    }
+
+    /// Get the ANSI style for a colorable path.
+    ///
+    /// With `ln=target`, symbolic links are styled like the file they point to (including its
+    /// name), and broken links like orphans (`or`).
+    pub fn style_for<F: Colorable>(&self, file: &F) -> Option<&Style> {
+        self.style_for_file_with_indicator(file, self.indicator_for(file))
+    }
+
+    /// Get the ANSI style for a colorable path, whose indicator was already determined.
+    fn style_for_file_with_indicator<F: Colorable>(
+        &self,
+        file: &F,
+        indicator: Indicator,
+    ) -> Option<&Style> {
+        if indicator == Indicator::SymbolicLink && self.symlink_as_target {
+            return self.style_for_symlink_target(&file.path());
+        }
+
+        if indicator == Indicator::RegularFile {
+            let filename = file.file_name();
+            if let Some(style) = self.suffixes.get(crate::fs::os_str_bytes(&filename)) {
                 return Some(style);
             }
         }
@@ -448,11 +1290,70 @@ impl LsColors {
         self.style_for_indicator(indicator)
     }
 
+    /// Get the ANSI style for the target of a symbolic link.
+    fn style_for_symlink_target(&self, path: &Path) -> Option<&Style> {
+        match symlink_target(path) {
+            Some((target, metadata)) => self.style_for_path_with_metadata(target, Some(&metadata)),
+            None => self.style_for_indicator(Indicator::OrphanedSymbolicLink),
+        }
+    }
+
+    /// Get the ANSI styles for many paths in parallel, using `rayon` (if the `rayon` feature is
+    /// enabled). The styles are returned in the same order as the paths.
+    #[cfg(feature = "rayon")]
+    pub fn par_style_for_paths<I>(&self, paths: I) -> Vec<Option<Style>>
+    where
+        I: rayon::iter::IntoParallelIterator,
+        I::Item: AsRef<Path>,
+    {
+        use rayon::iter::ParallelIterator;
+
+        paths
+            .into_par_iter()
+            .map(|path| self.style_for_path(path).copied())
+            .collect()
+    }
+
     /// Get the ANSI style for a string. This does not have to be a valid filepath.
+    ///
+    /// Only the suffix rules are used, but all of them: not just extensions like `*.gz`, but any
+    /// rule that matches the end of the string, like `*README`, `*~` or `*.d.ts`.
     pub fn style_for_str(&self, file_str: &str) -> Option<&Style> {
         self.suffixes.get(file_str)
     }
 
+    /// Get the suffix rule that matches a string (like [`style_for_str`](#method.style_for_str)),
+    /// together with its style and the length of the matched part.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    ///
+    /// let lscolors = LsColors::from_string("*.gz=01;31:*.tar.gz=01;33");
+    /// let suffix = lscolors.matched_suffix_for_str("foo.tar.gz").unwrap();
+    /// assert_eq!(b".tar.gz", suffix.pattern().as_slice());
+    /// assert_eq!(7, suffix.matched_len());
+    /// ```
+    pub fn matched_suffix_for_str(&self, file_str: &str) -> Option<MatchedSuffix<'_>> {
+        self.suffixes.get_match(file_str)
+    }
+
+    /// Get the length of the longest suffix rule in bytes (without the leading `*`), or zero if
+    /// there are no suffix rules. Only the last `max_suffix_len()` bytes of a file name are
+    /// relevant for matching, so shorter names can't match longer suffixes.
+    ///
+    /// *Note:* versioned suffixes like `*.so.*` are counted with their trailing `.*`, but they
+    /// also match names with longer version numbers, like `libfoo.so.1.2.3`.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    ///
+    /// let lscolors = LsColors::from_string("*.gz=01;31:*.tar.gz=01;33");
+    /// assert_eq!(7, lscolors.max_suffix_len());
+    /// ```
+    pub fn max_suffix_len(&self) -> usize {
+        self.suffixes.max_len()
+    }
+
     /// Get the ANSI style for a path, given the corresponding `Metadata` struct.
     ///
     /// *Note:* The `Metadata` struct must have been acquired via `Path::symlink_metadata` in
@@ -462,95 +1363,692 @@ impl LsColors {
         path: P,
         metadata: Option<&std::fs::Metadata>,
     ) -> Option<&Style> {
-        struct PathWithMetadata<'a> {
-            path: &'a Path,
-            metadata: Option<&'a Metadata>,
-        }
+        let path = path.as_ref();
+        self.style_for(&PathWithMetadata { path, metadata })
+    }
 
-        impl Colorable for PathWithMetadata<'_> {
-            fn path(&self) -> PathBuf {
-                self.path.to_owned()
-            }
+    /// Get the ANSI style for a path, given only its `FileType` (e.g. from
+    /// `DirEntry::file_type()`, which usually does not need a `stat` call). The indicators that
+    /// need the full metadata (like `su`, `ex` or `tw`) are skipped, so regular files and
+    /// directories get the `fi` (or suffix) and `di` styles. See also
+    /// [`needs_metadata_for`](#method.needs_metadata_for).
+    ///
+    /// *Note:* The `FileType` must not follow symbolic links in order to colorize them correctly.
+    pub fn style_for_path_with_file_type<P: AsRef<Path>>(
+        &self,
+        path: P,
+        file_type: Option<FileType>,
+    ) -> Option<&Style> {
+        let path = path.as_ref();
+        self.style_for(&PathWithFileType { path, file_type })
+    }
 
-            fn file_name(&self) -> OsString {
-                // Path::file_name() only works if the last component is Normal, but
-                // we want it for all component types, so we open code it
-
-                self.path
-                    .components()
-                    .last()
-                    .map(|c| c.as_os_str())
-                    .unwrap_or_else(|| self.path.as_os_str())
-                    .to_owned()
-            }
+    /// Explain which rule determines the style for a given path, e.g. to find out why a file is
+    /// shown in a certain color. Like [`style_for_path`](#method.style_for_path), this calls
+    /// `Path::symlink_metadata` internally.
+    ///
+    /// With `ln=target`, symbolic links are explained by their target, like they are styled.
+    pub fn explain_for_path<P: AsRef<Path>>(&self, path: P) -> Match {
+        let path = path.as_ref();
+        let metadata = path.symlink_metadata().ok();
+        self.explain_for(&PathWithMetadata {
+            path,
+            metadata: metadata.as_ref(),
+        })
+    }
 
-            fn file_type(&self) -> Option<FileType> {
-                self.metadata.map(|m| m.file_type())
-            }
+    /// Explain which rule determines the style for a colorable path, see
+    /// [`explain_for_path`](#method.explain_for_path).
+    fn explain_for<F: Colorable>(&self, file: &F) -> Match {
+        let indicator = self.indicator_for(file);
 
-            fn metadata(&self) -> Option<Metadata> {
-                self.metadata.cloned()
-            }
+        if indicator == Indicator::SymbolicLink && self.symlink_as_target {
+            return match symlink_target(&file.path()) {
+                Some((target, metadata)) => self.explain_for(&PathWithMetadata {
+                    path: &target,
+                    metadata: Some(&metadata),
+                }),
+                None => Match {
+                    indicator: Indicator::OrphanedSymbolicLink,
+                    suffix: None,
+                    style: self
+                        .style_for_indicator(Indicator::OrphanedSymbolicLink)
+                        .copied(),
+                },
+            };
         }
 
-        let path = path.as_ref();
-        self.style_for(&PathWithMetadata { path, metadata })
+        let suffix = if indicator == Indicator::RegularFile {
+            let file_name = file.file_name();
+            self.suffixes
+                .get_match(crate::fs::os_str_bytes(&file_name))
+                .map(|suffix| {
+                    (
+                        String::from_utf8_lossy(&suffix.pattern()).into_owned(),
+                        suffix.style().copied(),
+                    )
+                })
+        } else {
+            None
+        };
+
+        Match {
+            indicator,
+            suffix,
+            style: self.style_for_file_with_indicator(file, indicator).copied(),
+        }
     }
 
     /// Get ANSI styles for each component of a given path. Components already include the path
     /// separator symbol, if required. For a path like `foo/bar/test.md`, this would return an
     /// iterator over three pairs for the three path components `foo/`, `bar/` and `test.md`
     /// together with their respective styles.
+    ///
+    /// *Note:* this calls `Path::symlink_metadata` for every component. If you already happen to
+    /// have the metadata available, use
+    /// [`style_for_path_components_with`](#method.style_for_path_components_with).
     pub fn style_for_path_components<'a>(&'a self, path: &'a Path) -> StyledComponents<'a> {
+        self.style_for_path_components_with(path, |path| path.symlink_metadata().ok())
+    }
+
+    /// Like [`style_for_path_components`](#method.style_for_path_components), but the metadata of
+    /// each (accumulated) component path is provided by the given function instead of being
+    /// queried from the file system. This allows callers that walk a directory tree to reuse
+    /// metadata they already have, instead of `stat`ing every parent directory again.
+    ///
+    /// The metadata must have been acquired via `Path::symlink_metadata` in order to colorize
+    /// symbolic links correctly.
+    pub fn style_for_path_components_with<'a, F>(
+        &'a self,
+        path: &'a Path,
+        metadata: F,
+    ) -> StyledComponents<'a, F>
+    where
+        F: FnMut(&Path) -> Option<Metadata>,
+    {
         StyledComponents {
             lscolors: self,
+            metadata,
             component_path: PathBuf::new(),
             components: path.components().peekable(),
         }
     }
 
+    /// Get the full paths and (owned) ANSI styles of all ancestors of a given path, from the root
+    /// down to the path itself. For a path like `foo/bar/test.md`, this would return the paths
+    /// `foo`, `foo/bar` and `foo/bar/test.md` together with their respective styles.
+    ///
+    /// Every ancestor is `stat`ed separately, so symbolic links along the way are styled as such.
+    pub fn ancestry_styles(&self, path: &Path) -> Vec<(PathBuf, Option<Style>)> {
+        let mut ancestor = PathBuf::new();
+        path.components()
+            .map(|component| {
+                ancestor.push(component);
+                let style = self.style_for_path(&ancestor).cloned();
+                (ancestor.clone(), style)
+            })
+            .collect()
+    }
+
+    /// Get all rules whose style differs from the default configuration, together with the
+    /// default and the current style. Indicators are listed first (in declaration order),
+    /// followed by suffixes (in the order they first appear).
+    ///
+    /// Only explicitly configured styles are compared, fallbacks (like `mi` to `or`) are not
+    /// taken into account.
+    pub fn diff_from_default(&self) -> Vec<(Rule, Option<Style>, Option<Style>)> {
+        let default = Self::default();
+
+        let mut diff: Vec<_> = Indicator::ALL
+            .iter()
+            .filter_map(|&indicator| {
+                let default_style = default.indicator_mapping.get(indicator).cloned();
+                let style = self.indicator_mapping.get(indicator).cloned();
+                (default_style != style).then_some((
+                    Rule::Indicator(indicator),
+                    default_style,
+                    style,
+                ))
+            })
+            .collect();
+
+        let default_suffixes = default.suffix_rules();
+        let suffixes = self.suffix_rules();
+
+        let default_style_for = |suffix: &str| {
+            default_suffixes
+                .iter()
+                .find(|(s, _)| s == suffix)
+                .and_then(|(_, style)| *style)
+        };
+        for (suffix, style) in &suffixes {
+            let default_style = default_style_for(suffix);
+            if default_style != *style {
+                diff.push((Rule::Suffix(suffix.clone()), default_style, *style));
+            }
+        }
+        for (suffix, default_style) in &default_suffixes {
+            if default_style.is_some() && !suffixes.iter().any(|(s, _)| s == suffix) {
+                diff.push((Rule::Suffix(suffix.clone()), *default_style, None));
+            }
+        }
+
+        diff
+    }
+
+    /// Get the effective style of each distinct suffix, in the order the suffixes first appear.
+    fn suffix_rules(&self) -> Vec<(String, Option<Style>)> {
+        let mut rules: Vec<(String, Option<Style>)> = Vec::new();
+        for (suffix, style) in self.suffixes.iter() {
+            let suffix = String::from_utf8_lossy(&suffix).into_owned();
+            if let Some(rule) = rules.iter_mut().find(|(s, _)| *s == suffix) {
+                rule.1 = style.cloned();
+            } else {
+                rules.push((suffix, style.cloned()));
+            }
+        }
+        rules
+    }
+
+    /// Apply a transformation to all styles, e.g. to make every style bold or to replace colors
+    /// by high-contrast variants. This affects all indicator and suffix styles that are currently
+    /// configured (styles that are added later, e.g. via `Extend`, are not transformed).
+    ///
+    /// The transformation is applied once, up front, so the `style_for_*` lookups still return
+    /// references and do not have any additional cost. Calling this repeatedly composes the
+    /// transformations.
+    pub fn transform_styles<F: FnMut(Style) -> Style>(&mut self, mut transform: F) {
+        let indicator_mapping = Arc::make_mut(&mut self.indicator_mapping);
+        let suffixes = Arc::make_mut(&mut self.suffixes);
+        for style in indicator_mapping.values_mut().chain(suffixes.styles_mut()) {
+            *style = transform(*style);
+        }
+    }
+
+    /// Find all colors that can not be displayed by a terminal supporting the given [ColorLevel],
+    /// e.g. `RGB` colors on a 256-color terminal, or `Fixed` colors on a 16-color terminal.
+    /// Indicators are listed first (in declaration order), followed by suffixes.
+    pub fn validate_for_level(&self, level: ColorLevel) -> Vec<Problem> {
+        let indicators = Indicator::ALL.iter().map(|&indicator| {
+            let style = self.indicator_mapping.get(indicator).cloned();
+            (Rule::Indicator(indicator), style)
+        });
+        let suffixes = self
+            .suffix_rules()
+            .into_iter()
+            .map(|(suffix, style)| (Rule::Suffix(suffix), style));
+
+        indicators
+            .chain(suffixes)
+            .flat_map(|(rule, style)| {
+                style
+                    .into_iter()
+                    .flat_map(|style| style.colors().collect::<Vec<_>>())
+                    .filter(|color| color.level() > level)
+                    .map(move |color| Problem {
+                        rule: rule.clone(),
+                        color,
+                    })
+            })
+            .collect()
+    }
+
+    /// Layer the `no` (normal) style underneath all other styles: with `no=37:di=01`, directories
+    /// are shown bold *and* white. Colors of the specific style take precedence over the ones
+    /// from `no`, font-style attributes of both are combined.
+    ///
+    /// *Note:* This is not what GNU `ls` does. It emits `no`, resets, and then emits the specific
+    /// style, so with `no=37:di=01` directories are only bold (see
+    /// [`write_styled`](#method.write_styled)). By default, the `no` style is only used as a
+    /// fallback for indicators without any style. Like
+    /// [`transform_styles`](#method.transform_styles), this only affects the styles that are
+    /// currently configured.
+    pub fn layer_normal_style(&mut self) {
+        let normal = match self.indicator_mapping.get(Indicator::Normal) {
+            Some(normal) => *normal,
+            None => return,
+        };
+
+        self.transform_styles(|style| normal.combine(&style));
+    }
+
+    /// Get the character that `ls -F` (`--classify`) appends to a file name of the given type:
+    /// `/` for directories, `@` for symbolic links, `*` for executables, `|` for FIFOs, `=` for
+    /// sockets and `>` for doors. Returns `None` for all other indicators.
+    ///
+    /// Note that `ls -F` also appends `*` to executables that are shown with a more specific
+    /// indicator (like `su` or `sg`).
+    pub fn type_suffix_char(&self, indicator: Indicator) -> Option<char> {
+        match indicator {
+            Indicator::Directory
+            | Indicator::StickyAndOtherWritable
+            | Indicator::OtherWritable
+            | Indicator::Sticky => Some('/'),
+            Indicator::SymbolicLink | Indicator::OrphanedSymbolicLink => Some('@'),
+            Indicator::ExecutableFile => Some('*'),
+            Indicator::FIFO => Some('|'),
+            Indicator::Socket => Some('='),
+            Indicator::Door => Some('>'),
+            _ => None,
+        }
+    }
+
+    /// Get the character that `ls -F` (`--classify`) appends to the name of a given path. Unlike
+    /// [`type_suffix_char`](#method.type_suffix_char) for the indicator of the path, this only
+    /// depends on the file type and the mode bits, so it also works for files whose indicator
+    /// has no color (like executables with `ex=00`, or setuid executables). This calls
+    /// `Path::symlink_metadata` internally.
+    pub fn type_suffix_char_for_path<P: AsRef<Path>>(&self, path: P) -> Option<char> {
+        let path = path.as_ref();
+        let metadata = path.symlink_metadata().ok()?;
+        let file_type = metadata.file_type();
+
+        let indicator = if file_type.is_dir() {
+            Indicator::Directory
+        } else if file_type.is_symlink() {
+            Indicator::SymbolicLink
+        } else if file_type.is_file() {
+            let executable = crate::fs::mode(&metadata) & 0o0111 != 0
+                || path.file_name().is_some_and(crate::fs::is_executable_name);
+            if !executable {
+                return None;
+            }
+            Indicator::ExecutableFile
+        } else {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::FileTypeExt;
+
+                if file_type.is_fifo() {
+                    Indicator::FIFO
+                } else if file_type.is_socket() {
+                    Indicator::Socket
+                } else {
+                    return None;
+                }
+            }
+
+            #[cfg(not(unix))]
+            return None;
+        };
+
+        self.type_suffix_char(indicator)
+    }
+
+    /// Get the literal value of one of the code indicators `lc` (left code), `rc` (right code),
+    /// `ec` (end code), `rs` (reset) and `cl` (clear line). Escape sequences like `\e` or `^[`
+    /// are already decoded. Returns `None` for all other indicators, or if the code is not set.
+    pub fn code_for_indicator(&self, indicator: Indicator) -> Option<&str> {
+        self.code_mapping.get(indicator).map(String::as_str)
+    }
+
+    /// Get the left code (`lc`), which is printed before a color sequence. Defaults to `\x1b[`.
+    pub fn left_code(&self) -> Option<&str> {
+        self.code_for_indicator(Indicator::LeftCode)
+    }
+
+    /// Get the right code (`rc`), which is printed after a color sequence. Defaults to `m`.
+    pub fn right_code(&self) -> Option<&str> {
+        self.code_for_indicator(Indicator::RightCode)
+    }
+
+    /// Get the end code (`ec`), which replaces `lc`, `rs` and `rc` to end a colored file name, if
+    /// set.
+    pub fn end_code(&self) -> Option<&str> {
+        self.code_for_indicator(Indicator::EndCode)
+    }
+
+    /// Get the reset code (`rs`), which resets to ordinary colors. Defaults to `0`.
+    pub fn reset_code(&self) -> Option<&str> {
+        self.code_for_indicator(Indicator::Reset)
+    }
+
+    /// Get the code to clear to the end of the line (`cl`). Defaults to `\x1b[K`.
+    pub fn clear_line(&self) -> Option<&str> {
+        self.code_for_indicator(Indicator::ClearLine)
+    }
+
+    /// Write `text` in the given style, framed with the configured codes exactly like GNU `ls`
+    /// does: `lc`, the SGR parameters of the style and `rc` before the text, and `ec` (or `lc`,
+    /// `rs` and `rc`, if `ec` is not set) after it. The codes default to the GNU defaults
+    /// (`\x1b[`, `m` and `0`) if they are not configured.
+    ///
+    /// Like `ls`, this first resets to the normal colors, and uses the `no` style as a basis if
+    /// it is set. If neither `no` nor the given style have any attributes, only the text is
+    /// written.
+    ///
+    /// *Note:* GNU `ls` only emits the initial reset once at the start of its output, so this
+    /// matches the output of `ls --color=always` for a single file. The style is re-encoded (see
+    /// [`Style::to_ansi_sequence`]), so `40;31;01` is written as `01;31;40`.
+    pub fn write_styled<W: Write>(&self, out: &mut W, text: &str, style: &Style) -> io::Result<()> {
+        self.write_framed(out, text, style).map(|_| ())
+    }
+
+    /// Like [`LsColors::write_styled`], but also emit the clear-to-end-of-line code `cl` (or
+    /// `\x1b[K`, if it is not set) after the text where GNU `ls` does, to avoid background
+    /// colors bleeding into the next line on some terminals.
+    ///
+    /// GNU `ls` emits `cl` right after the end code if the text was colored, `line_length` is
+    /// not zero, and the text wraps onto another line, i.e. if `start_col / line_length` differs
+    /// from `(start_col + width - 1) / line_length`. Here, `start_col` is the column the text
+    /// starts at, `line_length` is the width of the terminal and `width` is the number of
+    /// characters in `text`.
+    pub fn write_styled_with_clear<W: Write>(
+        &self,
+        out: &mut W,
+        text: &str,
+        style: &Style,
+        start_col: usize,
+        line_length: usize,
+    ) -> io::Result<()> {
+        let colored = self.write_framed(out, text, style)?;
+
+        let width = text.chars().count();
+        if colored
+            && line_length != 0
+            && width != 0
+            && start_col / line_length != (start_col + width - 1) / line_length
+        {
+            out.write_all(self.clear_line().unwrap_or("\x1b[K").as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a path with every component in its own style (see
+    /// [`style_for_path_components`](#method.style_for_path_components)) directly to `out`.
+    ///
+    /// Each styled component is painted like [`Style::paint`] does, including its trailing
+    /// separator. Instead of allocating a string for every component, the escape sequences are
+    /// encoded into a single buffer that is reused for the whole path.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    /// use std::path::Path;
+    ///
+    /// let lscolors = LsColors::from_string("*.md=01;33");
+    ///
+    /// let mut out = Vec::new();
+    /// lscolors.write_path(&mut out, Path::new("README.md")).unwrap();
+    /// assert_eq!(b"\x1b[01;33mREADME.md\x1b[0m", &out[..]);
+    /// ```
+    pub fn write_path<W: Write>(&self, out: &mut W, path: &Path) -> io::Result<()> {
+        self.write_components(out, self.style_for_path_components(path), false)
+    }
+
+    /// Like [`write_path`](#method.write_path), but only the last component of the path (the
+    /// file name) is styled. The other components are written unchanged, and only the path
+    /// itself is `stat`ed.
+    ///
+    /// ```
+    /// use lscolors::LsColors;
+    /// use std::path::Path;
+    ///
+    /// let lscolors = LsColors::from_string("di=01;34:*.md=01;33");
+    ///
+    /// let mut out = Vec::new();
+    /// lscolors.write_path_basename(&mut out, Path::new("README.md")).unwrap();
+    /// assert_eq!(b"\x1b[01;33mREADME.md\x1b[0m", &out[..]);
+    /// ```
+    pub fn write_path_basename<W: Write>(&self, out: &mut W, path: &Path) -> io::Result<()> {
+        let components = self.style_for_path_components_with(path, |component_path| {
+            if component_path == path {
+                component_path.symlink_metadata().ok()
+            } else {
+                None
+            }
+        });
+        self.write_components(out, components, true)
+    }
+
+    /// Write the styled components of a path, see [`write_path`](#method.write_path). If
+    /// `only_basename` is set, only the last component is styled.
+    fn write_components<W, F>(
+        &self,
+        out: &mut W,
+        mut components: StyledComponents<'_, F>,
+        only_basename: bool,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        F: FnMut(&Path) -> Option<Metadata>,
+    {
+        let mut separator = [0; 4];
+        let separator = MAIN_SEPARATOR.encode_utf8(&mut separator).as_bytes();
+        let mut sequence = String::new();
+
+        while let Some((component, trailing_separator, style)) = components.next_component() {
+            let is_basename = components.components.peek().is_none();
+            let style =
+                style.filter(|style| !style.is_default() && (is_basename || !only_basename));
+            if let Some(style) = style {
+                sequence.clear();
+                // Writing to a `String` cannot fail
+                let _ = style.write_ansi_sequence(&mut sequence);
+                write!(out, "\x1b[{}m", sequence)?;
+            }
+
+            out.write_all(&crate::fs::os_str_bytes(component))?;
+            if trailing_separator {
+                out.write_all(separator)?;
+            }
+
+            if style.is_some() {
+                out.write_all(b"\x1b[0m")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write `text` like [`LsColors::write_styled`], and return whether it was framed with codes.
+    fn write_framed<W: Write>(&self, out: &mut W, text: &str, style: &Style) -> io::Result<bool> {
+        let normal = self.indicator_mapping.get(Indicator::Normal);
+        if style.is_default() && normal.is_none() {
+            out.write_all(text.as_bytes())?;
+            return Ok(false);
+        }
+
+        let left = self.left_code().unwrap_or("\x1b[");
+        let right = self.right_code().unwrap_or("m");
+
+        self.write_reset(out)?;
+        if let Some(normal) = normal {
+            write!(out, "{}{}{}", left, normal.to_ansi_sequence(), right)?;
+        }
+        if !style.is_default() {
+            if normal.is_some() {
+                // Reset, so the attributes of `no` and the style are not combined
+                write!(out, "{}{}", left, right)?;
+            }
+            write!(out, "{}{}{}", left, style.to_ansi_sequence(), right)?;
+        }
+        out.write_all(text.as_bytes())?;
+        self.write_reset(out)?;
+
+        Ok(true)
+    }
+
+    /// Write the end code `ec`, or `lc`, `rs` and `rc` if it is not set.
+    fn write_reset<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self.end_code() {
+            Some(end) => out.write_all(end.as_bytes()),
+            None => write!(
+                out,
+                "{}{}{}",
+                self.left_code().unwrap_or("\x1b["),
+                self.reset_code().unwrap_or("0"),
+                self.right_code().unwrap_or("m")
+            ),
+        }
+    }
+
     /// Get the ANSI style for a certain `Indicator` (regular file, directory, symlink, ...). Note
     /// that this function implements a fallback logic for some of the indicators (just like `ls`).
     /// For example, the style for `mi` (missing file) falls back to `or` (orphaned symbolic link)
-    /// if it has not been specified explicitly.
+    /// if it has not been specified explicitly. The fallbacks can be changed with
+    /// [`fallback_policy`](#method.fallback_policy).
     pub fn style_for_indicator(&self, indicator: Indicator) -> Option<&Style> {
         self.indicator_mapping
-            .get(&indicator)
+            .get(indicator)
             .or_else(|| {
-                self.indicator_mapping.get(&match indicator {
-                    Indicator::Setuid
-                    | Indicator::Setgid
-                    | Indicator::ExecutableFile
-                    | Indicator::MultipleHardLinks => Indicator::RegularFile,
-
-                    Indicator::StickyAndOtherWritable
-                    | Indicator::OtherWritable
-                    | Indicator::Sticky => Indicator::Directory,
-
-                    Indicator::OrphanedSymbolicLink => Indicator::SymbolicLink,
-
-                    Indicator::MissingFile => Indicator::OrphanedSymbolicLink,
-
-                    _ => indicator,
-                })
+                self.indicator_mapping
+                    .get(self.fallback.fallback_for(indicator)?)
             })
             .or_else(|| {
-                if indicator == Indicator::RegularFile && !self.file_normal_fallback {
+                if !self.fallback.normal_fallback() || self.reset_indicators.contains_key(indicator)
+                {
                     None
                 } else {
-                    self.indicator_mapping.get(&Indicator::Normal)
+                    self.indicator_mapping.get(Indicator::Normal)
                 }
             })
     }
+
+    /// Get the ANSI style for a two-letter indicator code like `di` or `ln`, with the same
+    /// fallback logic as [`style_for_indicator`](#method.style_for_indicator). Returns `None`
+    /// for unknown codes.
+    pub fn style_for_indicator_str(&self, code: &str) -> Option<&Style> {
+        self.style_for_indicator(Indicator::from(code)?)
+    }
+}
+
+/// The structured representation of [LsColors] used for (de)serialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LsColorsConfig {
+    /// Indicator styles. A `None` style (an explicit reset) disables the fallback to `no`.
+    indicators: BTreeMap<Indicator, Option<Style>>,
+
+    /// Literal values of the code indicators (`lc`, `rc`, ...).
+    #[serde(default)]
+    codes: BTreeMap<Indicator, String>,
+
+    /// Suffix styles, in order (later rules win).
+    suffixes: Vec<(String, Option<Style>)>,
+
+    /// Whether symbolic links are styled like their targets (`ln=target`).
+    #[serde(default)]
+    symlink_as_target: bool,
+
+    /// The fallbacks for indicators without a style (GNU fallbacks by default).
+    #[serde(default)]
+    fallback: FallbackPolicy,
+
+    /// Extensions of executable files, for files without metadata.
+    #[serde(default)]
+    executable_extensions: Vec<String>,
+
+    /// Whether all suffixes are matched case-sensitively.
+    #[serde(default)]
+    case_sensitive_suffixes: bool,
+
+    /// Whether suffixes are matched case-insensitively with the Unicode lowercase mapping.
+    #[serde(default)]
+    unicode_case_insensitive_suffixes: bool,
+
+    /// How to choose between multiple matching suffixes.
+    #[serde(default)]
+    suffix_match: SuffixMatch,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LsColors {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut indicators: BTreeMap<_, _> = self
+            .indicator_mapping
+            .iter()
+            .map(|(indicator, style)| (indicator, Some(*style)))
+            .collect();
+        for (indicator, ()) in self.reset_indicators.iter() {
+            indicators.entry(indicator).or_insert(None);
+        }
+
+        let suffixes = self
+            .suffixes
+            .iter()
+            .map(|(suffix, style)| {
+                (
+                    String::from_utf8_lossy(&suffix).into_owned(),
+                    style.cloned(),
+                )
+            })
+            .collect();
+
+        let codes = self
+            .code_mapping
+            .iter()
+            .map(|(indicator, code)| (indicator, code.clone()))
+            .collect();
+
+        let executable_extensions = self
+            .executable_extensions
+            .iter()
+            .flat_map(|extensions| extensions.split(';'))
+            .map(|extension| extension.to_owned())
+            .collect();
+
+        LsColorsConfig {
+            indicators,
+            codes,
+            suffixes,
+            symlink_as_target: self.symlink_as_target,
+            fallback: self.fallback.clone(),
+            executable_extensions,
+            case_sensitive_suffixes: self.suffixes.is_case_sensitive(),
+            unicode_case_insensitive_suffixes: self.suffixes.is_unicode_case_insensitive(),
+            suffix_match: self.suffixes.match_mode(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LsColors {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = LsColorsConfig::deserialize(deserializer)?;
+
+        let mut builder = LsColorsBuilder::empty();
+        for (indicator, style) in config.indicators {
+            builder.set_indicator(indicator, style);
+        }
+        for (indicator, code) in config.codes {
+            if indicator.is_code() {
+                builder.code_mapping.insert(indicator, code);
+            }
+        }
+        for (suffix, style) in config.suffixes {
+            builder.suffixes.push(suffix, style);
+        }
+        builder.symlink_as_target = config.symlink_as_target;
+        builder
+            .suffixes
+            .case_sensitive(config.case_sensitive_suffixes);
+        builder
+            .suffixes
+            .unicode_case_insensitive(config.unicode_case_insensitive_suffixes);
+        builder.suffixes.match_mode(config.suffix_match);
+
+        let extensions: Vec<_> = config
+            .executable_extensions
+            .iter()
+            .map(String::as_str)
+            .collect();
+        Ok(builder
+            .build()
+            .fallback_policy(config.fallback)
+            .executable_extensions(&extensions))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::style::{Color, FontStyle, Style};
-    use crate::{Indicator, LsColors};
+    use crate::style::{Color, ColorLevel, FontStyle, Style};
+    use crate::{Indicator, LsColors, LsColorsBuilder, Match, Problem, Rule};
 
     use std::fs::{self, File};
     use std::path::{Path, PathBuf};
+    use std::sync::Arc;
 
     #[test]
     fn basic_usage() {
@@ -608,6 +2106,109 @@ mod tests {
         assert_eq!(None, style_readme.background);
     }
 
+    #[test]
+    fn case_sensitive_suffixes() {
+        let lscolors = LsColors::from_string("*.jpg=35:*README=33:*readme=34");
+        let fg = |lscolors: &LsColors, name| lscolors.style_for_str(name).unwrap().foreground;
+        assert_eq!(Some(Color::Magenta), fg(&lscolors, "img.JPG"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "README"));
+        assert_eq!(Some(Color::Blue), fg(&lscolors, "readme"));
+
+        let lscolors = lscolors.case_sensitive_suffixes(true);
+        assert_eq!(None, lscolors.style_for_str("img.JPG"));
+        assert_eq!(None, lscolors.style_for_str("img.Jpg"));
+        assert_eq!(Some(Color::Magenta), fg(&lscolors, "img.jpg"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "README"));
+        assert_eq!(Some(Color::Blue), fg(&lscolors, "readme"));
+        assert_eq!(None, lscolors.style_for_str("ReadMe"));
+
+        // Back to the default behavior
+        let lscolors = lscolors.case_sensitive_suffixes(false);
+        assert_eq!(Some(Color::Magenta), fg(&lscolors, "img.JPG"));
+    }
+
+    #[test]
+    fn style_for_str_long_names() {
+        let long_name = "x".repeat(10_000);
+
+        // Short suffixes (stack path), regardless of the length of the name
+        let lscolors = LsColors::from_string("*.gz=01;31:*README=33");
+        let style = lscolors
+            .style_for_str(&format!("{}.GZ", long_name))
+            .unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        let style = lscolors
+            .style_for_str(&format!("{}README", long_name))
+            .unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+        assert_eq!(None, lscolors.style_for_str(&long_name));
+
+        // Suffixes longer than the stack buffer (heap path)
+        let long_suffix = format!(".{}", "y".repeat(100));
+        let lscolors = LsColors::from_string(&format!("*.gz=01;31:*{}=35", long_suffix));
+        let style = lscolors
+            .style_for_str(&format!("{}{}", long_name, long_suffix))
+            .unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+        let style = lscolors
+            .style_for_str(&format!("{}.gz", long_name))
+            .unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        assert_eq!(None, lscolors.style_for_str(&long_suffix[1..]));
+    }
+
+    #[test]
+    fn style_for_str_versioned_suffix() {
+        let fg = |lscolors: &LsColors, name| {
+            lscolors
+                .style_for_str(name)
+                .and_then(|style| style.foreground)
+        };
+
+        // GNU ls only matches plain suffixes
+        let lscolors = LsColors::from_string("*.so=01;32");
+        assert_eq!(Some(Color::Green), fg(&lscolors, "libfoo.so"));
+        assert_eq!(None, fg(&lscolors, "libfoo.so.1"));
+
+        let lscolors = LsColors::from_string("*.so=01;32:*.so.*=33");
+        assert_eq!(Some(Color::Green), fg(&lscolors, "libfoo.so"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libc.so.6"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "ld-linux-x86-64.so.2"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libstdc++.so.6.0.30"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "LIBFOO.SO.1"));
+        assert_eq!(None, fg(&lscolors, "libfoo.so."));
+        assert_eq!(None, fg(&lscolors, "libfoo.so.1a"));
+        assert_eq!(None, fg(&lscolors, "libfoo.so.x"));
+        assert_eq!(None, fg(&lscolors, "libfoo.sox.1"));
+
+        // A more specific versioned pattern
+        let lscolors = LsColors::from_string("*.so.*=33:*.so.6.*=35");
+        assert_eq!(Some(Color::Magenta), fg(&lscolors, "libstdc++.so.6.0.30"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libstdc++.so.5.0.7"));
+
+        // Among all matching suffixes, the later one wins
+        let lscolors = LsColors::from_string("*.so.*=33:*.1=34");
+        assert_eq!(Some(Color::Blue), fg(&lscolors, "libfoo.so.1"));
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libfoo.so.2"));
+        let lscolors = LsColors::from_string("*.1=34:*.so.*=33");
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libfoo.so.1"));
+
+        // Suffixes that end in `*` are no version wildcards
+        let lscolors = LsColors::from_string("*.so.*=33:**=31");
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libfoo.so.1"));
+        assert_eq!(Some(Color::Red), fg(&lscolors, "glob*"));
+        let suffix = lscolors.matched_suffix_for_str("libfoo.so.1").unwrap();
+        assert_eq!(b".so.*".to_vec(), suffix.pattern());
+        assert_eq!(5, suffix.matched_len());
+
+        let lscolors = LsColors::from_string("*.*=31");
+        assert_eq!(None, fg(&lscolors, "libfoo.so.1"));
+        assert_eq!(Some(Color::Red), fg(&lscolors, "file.*"));
+        let lscolors = LsColors::from_string("*.so.*=33:*.*=31");
+        assert_eq!(Some(Color::Yellow), fg(&lscolors, "libfoo.so.1"));
+        assert_eq!(None, fg(&lscolors, "libfoo.1"));
+    }
+
     #[test]
     fn style_for_path_uses_lowercase_matching() {
         let lscolors = LsColors::from_string("*.O=01;35");
@@ -679,6 +2280,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn style_for_str_non_extension_suffixes() {
+        let lscolors = LsColors::from_string("*~=90:*Makefile=33:*.ts=34:*.d.ts=36:*README=01");
+
+        let foreground = |s| lscolors.style_for_str(s).and_then(|style| style.foreground);
+        assert_eq!(Some(Color::BrightBlack), foreground("notes.txt~"));
+        assert_eq!(Some(Color::BrightBlack), foreground("~"));
+        assert_eq!(Some(Color::Yellow), foreground("Makefile"));
+        assert_eq!(Some(Color::Yellow), foreground("GNUmakefile"));
+        assert_eq!(Some(Color::Blue), foreground("index.ts"));
+        assert_eq!(Some(Color::Cyan), foreground("index.d.ts"));
+        assert_eq!(None, foreground("Makefile.am"));
+
+        let style = lscolors.style_for_str("README").unwrap();
+        assert_eq!(FontStyle::bold(), style.font_style);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn style_for_non_utf8_file() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tmp_dir = temp_dir();
+        let file_name = OsStr::from_bytes(b"caf\xc3\xa9\xff.mp3");
+        let tmp_file = tmp_dir.path().join(file_name);
+        if File::create(&tmp_file).is_err() {
+            // Some file systems reject invalid UTF-8 in file names
+            return;
+        }
+
+        let lscolors = LsColors::from_string("*.mp3=00;36");
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+    }
+
     #[test]
     fn style_for_directory() {
         let tmp_dir = temp_dir();
@@ -719,16 +2356,147 @@ mod tests {
         assert_eq!(Some(Color::Red), style.foreground);
     }
 
+    #[cfg(unix)]
     #[test]
-    fn style_for_missing_file() {
-        let lscolors1 = LsColors::from_string("mi=01:or=33;44");
-
-        let style_missing = lscolors1
-            .style_for_indicator(Indicator::MissingFile)
-            .unwrap();
-        assert_eq!(FontStyle::bold(), style_missing.font_style);
+    fn style_for_devices() {
+        use std::os::unix::fs::FileTypeExt;
 
-        let lscolors2 = LsColors::from_string("or=33;44");
+        // Both use `01;33` by default
+        let lscolors = LsColors::default();
+        assert_eq!(
+            lscolors.style_for_indicator(Indicator::CharacterDevice),
+            lscolors.style_for_indicator(Indicator::BlockDevice)
+        );
+
+        let lscolors = LsColors::from_string("cd=35:bd=36");
+
+        let char_device = Path::new("/dev/null");
+        assert_eq!(
+            Indicator::CharacterDevice,
+            lscolors.explain_for_path(char_device).indicator
+        );
+        let style = lscolors.style_for_path(char_device).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+
+        // Not every system (or container) has a block device
+        let block_device = fs::read_dir("/dev").ok().and_then(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .find(|entry| entry.file_type().is_ok_and(|t| t.is_block_device()))
+        });
+        if let Some(block_device) = block_device {
+            let path = block_device.path();
+            assert_eq!(
+                Indicator::BlockDevice,
+                lscolors.explain_for_path(&path).indicator
+            );
+            let style = lscolors.style_for_path(&path).unwrap();
+            assert_eq!(Some(Color::Cyan), style.foreground);
+
+            let style = lscolors.style_for(&block_device).unwrap();
+            assert_eq!(Some(Color::Cyan), style.foreground);
+        }
+    }
+
+    #[test]
+    fn style_for_symlink_as_target() {
+        let tmp_dir = temp_dir();
+        let tmp_file_path = create_file(tmp_dir.path().join("image.png"));
+        let tmp_dir_path = create_dir(tmp_dir.path().join("dir"));
+
+        let symlink = tmp_dir.path().join("link");
+        create_symlink(&tmp_file_path, &symlink);
+        let relative_symlink = tmp_dir.path().join("relative-link");
+        create_symlink(&PathBuf::from("image.png"), &relative_symlink);
+        let dir_symlink = tmp_dir.path().join("dir-link");
+        create_symlink(&tmp_dir_path, &dir_symlink);
+        let broken_symlink = tmp_dir.path().join("broken-link");
+        create_symlink(&tmp_dir.path().join("missing.png"), &broken_symlink);
+
+        let lscolors = LsColors::from_string("ln=target:or=31:*.png=35");
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::SymbolicLink));
+
+        let fg = |path: &Path| lscolors.style_for_path(path).and_then(|s| s.foreground);
+        assert_eq!(Some(Color::Magenta), fg(&symlink));
+        assert_eq!(Some(Color::Magenta), fg(&relative_symlink));
+        assert_eq!(Some(Color::Blue), fg(&dir_symlink));
+        assert_eq!(Some(Color::Red), fg(&broken_symlink));
+
+        // A later style for `ln` overrides `ln=target`
+        let lscolors = LsColors::from_string("ln=target:ln=36:*.png=35");
+        let style = lscolors.style_for_path(&symlink).unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn style_for_windows_directory_links() {
+        use std::process::Command;
+
+        let tmp_dir = temp_dir();
+        let target = create_dir(tmp_dir.path().join("target"));
+        let missing = tmp_dir.path().join("missing");
+
+        let lscolors = LsColors::from_string("di=34:ln=36:or=31");
+        let fg = |path: &Path| {
+            lscolors
+                .style_for_path(path)
+                .and_then(|style| style.foreground)
+        };
+
+        // Junctions can be created without any privileges
+        let junction = tmp_dir.path().join("junction");
+        let status = Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(&junction)
+            .arg(&target)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        assert_eq!(Some(Color::Cyan), fg(&junction));
+
+        let broken_junction = tmp_dir.path().join("broken-junction");
+        let temp_target = create_dir(tmp_dir.path().join("temp-target"));
+        let status = Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(&broken_junction)
+            .arg(&temp_target)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        fs::remove_dir(&temp_target).unwrap();
+        assert_eq!(Some(Color::Red), fg(&broken_junction));
+
+        // Symbolic links need privileges (or developer mode)
+        let symlink = tmp_dir.path().join("symlink");
+        if std::os::windows::fs::symlink_dir(&target, &symlink).is_err() {
+            return;
+        }
+        assert_eq!(Some(Color::Cyan), fg(&symlink));
+
+        let broken_symlink = tmp_dir.path().join("broken-symlink");
+        std::os::windows::fs::symlink_dir(&missing, &broken_symlink).unwrap();
+        assert_eq!(Some(Color::Red), fg(&broken_symlink));
+
+        assert_eq!(Some(Color::Blue), fg(&target));
+    }
+
+    #[test]
+    fn style_for_missing_file() {
+        let lscolors1 = LsColors::from_string("mi=01:or=33;44");
+
+        let style_missing = lscolors1
+            .style_for_indicator(Indicator::MissingFile)
+            .unwrap();
+        assert_eq!(FontStyle::bold(), style_missing.font_style);
+
+        let lscolors2 = LsColors::from_string("or=33;44");
 
         let style_missing = lscolors2
             .style_for_indicator(Indicator::MissingFile)
@@ -774,6 +2542,84 @@ mod tests {
         assert_eq!(Some(Color::Magenta), style.foreground);
     }
 
+    #[test]
+    fn needs_metadata_for() {
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("file"));
+        let file_type = Some(tmp_file.symlink_metadata().unwrap().file_type());
+        let dir_type = Some(tmp_dir.path().symlink_metadata().unwrap().file_type());
+
+        let lscolors = LsColors::from_string("su=0:sg=0:ex=0:mh=0:tw=0:ow=0:st=0");
+        assert!(!lscolors.needs_metadata_for(file_type));
+        assert!(!lscolors.needs_metadata_for(dir_type));
+        assert!(lscolors.needs_metadata_for(None));
+
+        let lscolors = LsColors::default();
+        assert!(lscolors.needs_metadata_for(file_type));
+        assert!(lscolors.needs_metadata_for(dir_type));
+    }
+
+    #[test]
+    fn style_for_path_fast() {
+        let rs = Style::from_ansi_sequence("31").unwrap();
+        let fi = Style::from_ansi_sequence("33").unwrap();
+        let lscolors =
+            LsColors::from_parts([(Indicator::RegularFile, fi)], [(".rs".into(), Some(rs))]);
+
+        // The file does not have to exist
+        let tmp_dir = temp_dir();
+        let missing = tmp_dir.path().join("missing.rs");
+        assert_eq!(Some(&rs), lscolors.style_for_path_fast(&missing));
+        assert_eq!(
+            Some(&fi),
+            lscolors.style_for_path_fast(tmp_dir.path().join("missing"))
+        );
+
+        // Suffix rules and `fi` don't apply to directories
+        let dir = create_dir(tmp_dir.path().join("dir.rs"));
+        assert_eq!(None, lscolors.style_for_path(&dir));
+
+        // With a style for directories, the metadata is used
+        let mut lscolors = lscolors;
+        lscolors.extend([(
+            Indicator::Directory,
+            Style::from_ansi_sequence("34").unwrap(),
+        )]);
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors.style_for_path_fast(&dir).unwrap().foreground
+        );
+        assert_eq!(Some(&rs), lscolors.style_for_path_fast(&missing));
+    }
+
+    #[cfg(all(feature = "capabilities", target_os = "linux"))]
+    #[test]
+    fn style_for_capabilities() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("ping"));
+        fs::set_permissions(&tmp_file, fs::Permissions::from_mode(0o755)).unwrap();
+
+        // VFS_CAP_REVISION_2 | VFS_CAP_FLAGS_EFFECTIVE, with CAP_NET_RAW permitted
+        let mut cap = Vec::new();
+        cap.extend_from_slice(&0x0200_0001u32.to_le_bytes());
+        cap.extend_from_slice(&(1u32 << 13).to_le_bytes());
+        cap.extend_from_slice(&[0; 12]);
+        if xattr::set(&tmp_file, "security.capability", &cap).is_err() {
+            // Setting capabilities requires privileges (CAP_SETFCAP)
+            return;
+        }
+
+        let lscolors = LsColors::from_string("ca=30;41:ex=01;32");
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Red), style.background);
+
+        let lscolors = LsColors::from_string("ex=01;32");
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+    }
+
     #[cfg(unix)]
     #[test]
     fn style_for_sticky_other_writable() {
@@ -836,6 +2682,133 @@ mod tests {
         assert_eq!(Some(Color::Blue), style_dir.unwrap().foreground);
     }
 
+    #[test]
+    fn style_for_path_components_borrowed() {
+        use std::borrow::Cow;
+        use std::path::MAIN_SEPARATOR;
+
+        let lscolors = LsColors::default();
+
+        let tmp_root = temp_dir();
+        let absolute = create_file(tmp_root.path().join("test-file"));
+        let relative = [
+            Path::new(".").join("foo").join("bar"),
+            Path::new("..").join("foo"),
+            Path::new("foo").join(".."),
+            PathBuf::from("foo"),
+        ];
+
+        for path in relative.iter().chain([&absolute]) {
+            let owned: Vec<_> = lscolors.style_for_path_components(path).collect();
+            let borrowed: Vec<_> = lscolors
+                .style_for_path_components(path)
+                .borrowed()
+                .collect();
+
+            assert_eq!(owned.len(), borrowed.len());
+            for ((o, o_style), (b, b_style)) in owned.iter().zip(borrowed.iter()) {
+                assert_eq!(o.as_os_str(), &**b);
+                assert_eq!(o_style, b_style);
+            }
+
+            // The last component never needs a separator
+            assert!(matches!(borrowed.last(), Some((Cow::Borrowed(_), _))));
+        }
+
+        let borrowed: Vec<_> = lscolors
+            .style_for_path_components(&relative[0])
+            .borrowed()
+            .map(|(c, _)| c.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            vec![
+                format!(".{}", MAIN_SEPARATOR),
+                format!("foo{}", MAIN_SEPARATOR),
+                "bar".to_string()
+            ],
+            borrowed
+        );
+
+        // The root directory is borrowed as well
+        #[cfg(unix)]
+        {
+            let (root, _) = lscolors
+                .style_for_path_components(&absolute)
+                .borrowed()
+                .next()
+                .unwrap();
+            assert!(matches!(root, Cow::Borrowed(_)));
+            assert_eq!("/", &*root);
+        }
+    }
+
+    #[test]
+    fn style_for_path_components_with() {
+        use std::collections::HashMap;
+
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        let tmp_file = create_file(tmp_dir.join("test-file.png"));
+
+        let lscolors = LsColors::from_string("di=34:*.png=36");
+
+        // Pre-computed metadata, e.g. from a directory walk
+        let known: HashMap<PathBuf, fs::Metadata> = tmp_file
+            .ancestors()
+            .filter_map(|p| Some((p.to_path_buf(), p.symlink_metadata().ok()?)))
+            .collect();
+
+        let mut calls = 0;
+        let components: Vec<_> = lscolors
+            .style_for_path_components_with(&tmp_file, |p| {
+                calls += 1;
+                known.get(p).cloned()
+            })
+            .collect();
+        let expected: Vec<_> = lscolors.style_for_path_components(&tmp_file).collect();
+
+        assert_eq!(expected, components);
+        assert_eq!(tmp_file.components().count(), calls);
+
+        // Without metadata, every component is treated like a regular file
+        let components: Vec<_> = lscolors
+            .style_for_path_components_with(&tmp_file, |_| None)
+            .collect();
+        let (_, style_dir) = &components[components.len() - 2];
+        assert_eq!(None, *style_dir);
+        let (_, style_file) = &components[components.len() - 1];
+        assert_eq!(Some(Color::Cyan), style_file.unwrap().foreground);
+    }
+
+    #[test]
+    fn ancestry_styles() {
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        create_file(tmp_dir.join("test-file.png"));
+
+        let tmp_symlink = tmp_root.path().join("test-symlink");
+        create_symlink(&tmp_dir, &tmp_symlink);
+
+        let path_via_symlink = tmp_symlink.join("test-file.png");
+
+        let lscolors = LsColors::from_string("di=34:ln=35:*.png=36");
+
+        let mut ancestry = lscolors.ancestry_styles(&path_via_symlink);
+        assert_eq!(path_via_symlink.components().count(), ancestry.len());
+
+        let (p_file, style_file) = ancestry.pop().unwrap();
+        assert_eq!(path_via_symlink, p_file);
+        assert_eq!(Some(Color::Cyan), style_file.unwrap().foreground);
+
+        let (p_symlink, style_symlink) = ancestry.pop().unwrap();
+        assert_eq!(tmp_symlink, p_symlink);
+        assert_eq!(Some(Color::Magenta), style_symlink.unwrap().foreground);
+
+        let (p_root, style_root) = ancestry.pop().unwrap();
+        assert_eq!(tmp_root.path(), p_root);
+        assert_eq!(Some(Color::Blue), style_root.unwrap().foreground);
+    }
+
     #[test]
     fn style_for_dir_entry() {
         use std::fs::read_dir;
@@ -851,6 +2824,235 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "walkdir")]
+    #[test]
+    fn style_for_walkdir_entry() {
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        create_file(tmp_dir.join("test-file.png"));
+
+        let lscolors = LsColors::from_string("di=01;34:*.png=01;35");
+
+        let styles: Vec<_> = walkdir::WalkDir::new(&tmp_dir)
+            .sort_by_file_name()
+            .into_iter()
+            .map(|entry| lscolors.style_for(&entry.unwrap()).unwrap().foreground)
+            .collect();
+        assert_eq!(vec![Some(Color::Blue), Some(Color::Magenta)], styles);
+    }
+
+    #[cfg(feature = "ignore")]
+    #[test]
+    fn style_for_ignore_entry() {
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        create_file(tmp_dir.join("test-file.png"));
+
+        let lscolors = LsColors::from_string("di=01;34:*.png=01;35");
+
+        let styles: Vec<_> = ignore::WalkBuilder::new(&tmp_dir)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build()
+            .map(|entry| lscolors.style_for(&entry.unwrap()).unwrap().foreground)
+            .collect();
+        assert_eq!(vec![Some(Color::Blue), Some(Color::Magenta)], styles);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn style_for_hidden_and_system() {
+        use std::fs::OpenOptions;
+        use std::os::windows::fs::OpenOptionsExt;
+
+        let tmp_dir = temp_dir();
+        let create_with_attributes = |name, attributes| {
+            let path = tmp_dir.path().join(name);
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .attributes(attributes)
+                .open(&path)
+                .expect("temporary file");
+            path
+        };
+        let hidden = create_with_attributes("hidden", crate::fs::FILE_ATTRIBUTE_HIDDEN);
+        let system = create_with_attributes(
+            "system",
+            crate::fs::FILE_ATTRIBUTE_HIDDEN | crate::fs::FILE_ATTRIBUTE_SYSTEM,
+        );
+        let normal = create_file(tmp_dir.path().join("normal"));
+
+        let hidden_style = Style::from_ansi_sequence("90").unwrap();
+        let system_style = Style::from_ansi_sequence("31").unwrap();
+        let mut lscolors = LsColors::from_string("fi=32");
+        lscolors.extend([
+            (Indicator::Hidden, hidden_style),
+            (Indicator::System, system_style),
+        ]);
+
+        assert_eq!(Some(&hidden_style), lscolors.style_for_path(&hidden));
+        assert_eq!(Some(&system_style), lscolors.style_for_path(&system));
+        assert_eq!(
+            Some(Color::Green),
+            lscolors.style_for_path(&normal).unwrap().foreground
+        );
+
+        // They can't be configured through `LS_COLORS`
+        let lscolors = LsColors::from_string("fi=32:hidden=90:system=31");
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::Hidden));
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::System));
+
+        // Without styles, hidden files are treated like all other files
+        let lscolors = LsColors::from_string("fi=32");
+        assert_eq!(
+            Some(Color::Green),
+            lscolors.style_for_path(&hidden).unwrap().foreground
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn style_for_executable_by_extension() {
+        let tmp_dir = temp_dir();
+        let exe = create_file(tmp_dir.path().join("program.EXE"));
+        let bat = create_file(tmp_dir.path().join("build.bat"));
+        let txt = create_file(tmp_dir.path().join("notes.txt"));
+
+        let lscolors = LsColors::from_string("fi=0:ex=01;32");
+        let fg = |path| {
+            lscolors
+                .style_for_path(path)
+                .and_then(|style| style.foreground)
+        };
+        assert_eq!(Some(Color::Green), fg(&exe));
+        assert_eq!(Some(Color::Green), fg(&bat));
+        assert_eq!(None, fg(&txt));
+
+        // Suffix rules take precedence
+        let lscolors = LsColors::from_string("ex=01;32:*.exe=35");
+        let fg = |path| {
+            lscolors
+                .style_for_path(path)
+                .and_then(|style| style.foreground)
+        };
+        assert_eq!(Some(Color::Magenta), fg(&exe));
+        assert_eq!(Some(Color::Green), fg(&bat));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn hidden_and_system_ignored_on_other_platforms() {
+        let tmp_dir = temp_dir();
+        let file = create_file(tmp_dir.path().join(".hidden"));
+        let file_type = Some(file.symlink_metadata().unwrap().file_type());
+
+        let mut lscolors = LsColors::from_string("su=0:sg=0:ex=0:mh=0:fi=32");
+        lscolors.extend([
+            (Indicator::Hidden, Style::from_ansi_sequence("90").unwrap()),
+            (Indicator::System, Style::from_ansi_sequence("31").unwrap()),
+        ]);
+
+        assert!(!lscolors.needs_metadata_for(file_type));
+        assert_eq!(
+            Some(Color::Green),
+            lscolors.style_for_path(&file).unwrap().foreground
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_style_for_paths() {
+        let tmp_dir = temp_dir();
+        let paths: Vec<_> = (0..200)
+            .map(|i| {
+                let path =
+                    tmp_dir
+                        .path()
+                        .join(format!("file-{}.{}", i, ["png", "rs", "gz"][i % 3]));
+                if i % 5 == 0 {
+                    create_dir(path)
+                } else {
+                    create_file(path)
+                }
+            })
+            .collect();
+
+        let lscolors = LsColors::from_string("*.png=35:*.gz=01;31");
+        let expected: Vec<_> = paths
+            .iter()
+            .map(|path| lscolors.style_for_path(path).copied())
+            .collect();
+        assert_eq!(expected, lscolors.par_style_for_paths(&paths));
+    }
+
+    #[test]
+    fn explain_for_path() {
+        let tmp_dir = temp_dir();
+        let archive = create_file(tmp_dir.path().join("archive.tar.gz"));
+        let other = create_file(tmp_dir.path().join("other.gz"));
+        let dir = create_dir(tmp_dir.path().join("dir.gz"));
+
+        let gz_style = Style::from_ansi_sequence("01;31").unwrap();
+        let tar_gz_style = Style::from_ansi_sequence("01;33").unwrap();
+        let lscolors = LsColors::from_string("*.gz=01;31:*.tar.gz=01;33");
+
+        assert_eq!(
+            Match {
+                indicator: Indicator::RegularFile,
+                suffix: Some((".tar.gz".into(), Some(tar_gz_style))),
+                style: Some(tar_gz_style),
+            },
+            lscolors.explain_for_path(&archive)
+        );
+        assert_eq!(
+            Some((".gz".into(), Some(gz_style))),
+            lscolors.explain_for_path(&other).suffix
+        );
+
+        // Suffixes are not used for directories
+        let explained = lscolors.explain_for_path(&dir);
+        assert_eq!(Indicator::Directory, explained.indicator);
+        assert_eq!(None, explained.suffix);
+        assert_eq!(lscolors.style_for_path(&dir).copied(), explained.style);
+
+        // The later rule wins
+        let lscolors = LsColors::from_string("*.tar.gz=01;33:*.gz=01;31");
+        assert_eq!(
+            Some((".gz".into(), Some(gz_style))),
+            lscolors.explain_for_path(&archive).suffix
+        );
+
+        // Disabled suffixes are reported, too
+        let lscolors = LsColors::from_string("fi=32:*.gz=01;31:*.gz=0");
+        let explained = lscolors.explain_for_path(&other);
+        assert_eq!(Some((".gz".into(), None)), explained.suffix);
+        assert_eq!(Some(Color::Green), explained.style.unwrap().foreground);
+
+        // With `ln=target`, links are explained by their target
+        let link = tmp_dir.path().join("link");
+        create_symlink(&archive, &link);
+        let lscolors = LsColors::from_string("ln=target:*.tar.gz=01;33");
+        assert_eq!(
+            Match {
+                indicator: Indicator::RegularFile,
+                suffix: Some((".tar.gz".into(), Some(tar_gz_style))),
+                style: Some(tar_gz_style),
+            },
+            lscolors.explain_for_path(&link)
+        );
+        assert_eq!(
+            lscolors.style_for_path(&link).copied(),
+            lscolors.explain_for_path(&link).style
+        );
+
+        let orphan = tmp_dir.path().join("orphan");
+        create_symlink(&tmp_dir.path().join("missing"), &orphan);
+        let lscolors = LsColors::from_string("ln=target:or=31");
+        let explained = lscolors.explain_for_path(&orphan);
+        assert_eq!(Indicator::OrphanedSymbolicLink, explained.indicator);
+        assert_eq!(Some(Color::Red), explained.style.unwrap().foreground);
+    }
+
     #[test]
     fn override_disable_suffix() {
         let tmp_dir = temp_dir();
@@ -875,6 +3077,1315 @@ mod tests {
         assert_eq!(None, style);
     }
 
+    #[test]
+    fn empty_value_resets_like_gnu() {
+        // GNU ls treats `di=` like `di=00`: the default style is dropped and following entries
+        // are parsed as usual
+        let lscolors = LsColors::from_string("di=:ln=01;36");
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::Directory));
+        let style_symlink = lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .unwrap();
+        assert_eq!(FontStyle::bold(), style_symlink.font_style);
+        assert_eq!(Some(Color::Cyan), style_symlink.foreground);
+
+        let tmp_dir = temp_dir();
+        assert_eq!(None, lscolors.style_for_path(tmp_dir.path()));
+
+        let lscolors_zero = LsColors::from_string("di=00:ln=01;36");
+        assert_eq!(
+            lscolors_zero.style_for_indicator(Indicator::Directory),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+
+        // An empty `fi` disables the fallback to `no`, just like `fi=0`
+        let tmp_file_path = create_file(tmp_dir.path().join("test-file"));
+        let lscolors = LsColors::from_string("no=01;31:fi=");
+        assert_eq!(None, lscolors.style_for_path(&tmp_file_path));
+
+        // The same goes for all other indicators: GNU ls emits `\e[m` for directories with
+        // `no=31:di=`, not the `no` style
+        for colors in ["no=31:di=", "no=31:di=0", "di=:no=31"] {
+            let lscolors = LsColors::from_string(colors);
+            assert_eq!(None, lscolors.style_for_indicator(Indicator::Directory));
+            assert_eq!(None, lscolors.style_for_path(tmp_dir.path()));
+        }
+
+        // Indicators which are not mentioned still fall back to `no`
+        let lscolors = LsColors::from_string("no=31:di=");
+        let style_file = lscolors
+            .style_for_indicator(Indicator::RegularFile)
+            .unwrap();
+        assert_eq!(Some(Color::Red), style_file.foreground);
+        let lscolors = LsColors::from_string("no=31:di=:di=01");
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(None, style_dir.foreground);
+
+        // An empty suffix entry overrides an earlier one
+        let lscolors = LsColors::from_string("*.png=01;35:*.png=");
+        assert_eq!(None, lscolors.style_for_str("test.png"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let lscolors = LsColors::default();
+        let json = serde_json::to_string(&lscolors).unwrap();
+        let roundtrip: LsColors = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, serde_json::to_string(&roundtrip).unwrap());
+        assert_eq!(
+            lscolors.style_for_indicator(Indicator::Directory),
+            roundtrip.style_for_indicator(Indicator::Directory)
+        );
+
+        // Suffix order and the `fi` fallback are preserved
+        let lscolors = LsColors::from_string("no=01;31:fi=0:*.gz=01;31:*.tar.gz=01;33");
+        let json = serde_json::to_string(&lscolors).unwrap();
+        let roundtrip: LsColors = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            Some(Color::Yellow),
+            roundtrip
+                .style_for_str("archive.tar.gz")
+                .unwrap()
+                .foreground
+        );
+        assert_eq!(
+            Some(Color::Red),
+            roundtrip.style_for_str("archive.gz").unwrap().foreground
+        );
+        assert_eq!(None, roundtrip.style_for_indicator(Indicator::RegularFile));
+
+        let lscolors = LsColors::from_string("ln=target");
+        let json = serde_json::to_string(&lscolors).unwrap();
+        assert!(json.contains(r#""symlink_as_target":true"#));
+        let roundtrip: LsColors = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, serde_json::to_string(&roundtrip).unwrap());
+
+        assert!(
+            serde_json::from_str::<LsColors>(r#"{"indicators":{"xx":null},"suffixes":[]}"#)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip_settings() {
+        use crate::{FallbackPolicy, SuffixMatch};
+
+        let lscolors = LsColors::from_string("ln=target:*.gz=32:*.TAR.GZ=31")
+            .suffix_match(SuffixMatch::Longest)
+            .case_sensitive_suffixes(true)
+            .unicode_case_insensitive_suffixes(true)
+            .fallback_policy(FallbackPolicy::none().with_normal_fallback(false))
+            .executable_extensions(&["sh"]);
+
+        let json = serde_json::to_string(&lscolors).unwrap();
+        let roundtrip: LsColors = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, serde_json::to_string(&roundtrip).unwrap());
+
+        assert!(roundtrip.symlink_as_target);
+        assert_eq!(SuffixMatch::Longest, roundtrip.suffixes.match_mode());
+        assert!(roundtrip.suffixes.is_case_sensitive());
+        assert!(roundtrip.suffixes.is_unicode_case_insensitive());
+        assert_eq!(
+            None,
+            roundtrip
+                .fallback
+                .fallback_for(Indicator::OrphanedSymbolicLink)
+        );
+        assert!(!roundtrip.fallback.normal_fallback());
+        assert_eq!(Some(".sh"), roundtrip.executable_extensions.as_deref());
+
+        assert_eq!(
+            Some(Color::Red),
+            roundtrip.style_for_str("a.TAR.GZ").unwrap().foreground
+        );
+        assert_eq!(
+            Some(Color::Green),
+            roundtrip.style_for_str("a.tar.gz").unwrap().foreground
+        );
+
+        // Missing settings keep their defaults
+        let lscolors: LsColors =
+            serde_json::from_str(r#"{"indicators":{},"suffixes":[]}"#).unwrap();
+        assert!(!lscolors.symlink_as_target);
+        assert_eq!(SuffixMatch::default(), lscolors.suffixes.match_mode());
+        assert!(lscolors.fallback.normal_fallback());
+    }
+
+    #[test]
+    fn diff_from_default() {
+        assert!(LsColors::default().diff_from_default().is_empty());
+
+        let lscolors =
+            LsColors::from_string("ex=01;32:di=01;31:ln=0:*.gz=01;31:*.tar=35:*.gz=01;33");
+        let diff = lscolors.diff_from_default();
+
+        let bold = |color| Style {
+            foreground: Some(color),
+            font_style: FontStyle::bold(),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                (
+                    Rule::Indicator(Indicator::Directory),
+                    Some(bold(Color::Blue)),
+                    Some(bold(Color::Red))
+                ),
+                (
+                    Rule::Indicator(Indicator::SymbolicLink),
+                    Some(bold(Color::Cyan)),
+                    None
+                ),
+                (Rule::Suffix(".gz".into()), None, Some(bold(Color::Yellow))),
+                (
+                    Rule::Suffix(".tar".into()),
+                    None,
+                    Style::from_ansi_sequence("35")
+                ),
+            ],
+            diff
+        );
+    }
+
+    #[test]
+    fn from_parts() {
+        let style = |code| Style::from_ansi_sequence(code);
+
+        let lscolors = LsColors::from_parts(
+            [(Indicator::Directory, style("01;34").unwrap())],
+            [
+                (".gz".to_string(), style("01;31")),
+                (".tar.gz".to_string(), style("01;33")),
+                (".png".to_string(), style("35")),
+                (".PNG".to_string(), None),
+                (".zip".to_string(), style("31")),
+                (".zip".to_string(), None),
+            ],
+        );
+
+        let mut builder = LsColorsBuilder::empty();
+        builder.add_from_string(
+            "di=01;34:*.gz=01;31:*.tar.gz=01;33:*.png=35:*.PNG=0:*.zip=31:*.zip=0",
+        );
+        let expected = builder.build();
+
+        for name in [
+            "foo.gz",
+            "foo.tar.gz",
+            "foo.png",
+            "foo.PNG",
+            "foo.zip",
+            "foo.txt",
+        ] {
+            assert_eq!(expected.style_for_str(name), lscolors.style_for_str(name));
+        }
+        assert_eq!(
+            style("01;33").as_ref(),
+            lscolors.style_for_str("foo.tar.gz")
+        );
+        assert_eq!(None, lscolors.style_for_str("foo.PNG"));
+        assert_eq!(None, lscolors.style_for_str("foo.zip"));
+
+        assert_eq!(
+            expected.style_for_indicator(Indicator::Directory),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::SymbolicLink));
+
+        let lscolors = LsColors::from_parts(
+            [
+                (Indicator::RightCode, Style::default()),
+                (Indicator::Reset, style("01").unwrap()),
+            ],
+            [],
+        );
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::RightCode));
+        assert_eq!(None, lscolors.right_code());
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::Reset));
+        assert_eq!(None, lscolors.reset_code());
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+        let dir_style = Style::from_ansi_sequence("01;34").unwrap();
+        let ln_style = Style::from_ansi_sequence("01;36").unwrap();
+
+        let mut lscolors: LsColors = [
+            (Indicator::Directory, dir_style),
+            (Indicator::SymbolicLink, ln_style),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            Some(&dir_style),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+        assert_eq!(
+            Some(&ln_style),
+            lscolors.style_for_indicator(Indicator::SymbolicLink)
+        );
+        // Starts out empty, like `LsColors::empty()`
+        assert_eq!(
+            None,
+            lscolors.style_for_indicator(Indicator::ExecutableFile)
+        );
+
+        let ex_style = Style::from_ansi_sequence("01;32").unwrap();
+        lscolors.extend([
+            (Indicator::ExecutableFile, ex_style),
+            (Indicator::Directory, ln_style),
+        ]);
+        assert_eq!(
+            Some(&ex_style),
+            lscolors.style_for_indicator(Indicator::ExecutableFile)
+        );
+        assert_eq!(
+            Some(&ln_style),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+
+        // Code indicators hold literal codes, not styles
+        lscolors.extend([(Indicator::LeftCode, Style::default())]);
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::LeftCode));
+        assert_eq!(None, lscolors.left_code());
+
+        let lscolors: LsColors = [(Indicator::EndCode, dir_style)].into_iter().collect();
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::EndCode));
+        assert_eq!(None, lscolors.end_code());
+    }
+
+    #[test]
+    fn validate_for_level() {
+        let lscolors = LsColors::from_string("rs=0:di=38;5;33:ln=36;48;2;0;0;0:*.png=38;2;255;0;0");
+
+        assert_eq!(
+            Vec::<Problem>::new(),
+            lscolors.validate_for_level(ColorLevel::TrueColor)
+        );
+        assert_eq!(
+            vec![
+                Problem {
+                    rule: Rule::Indicator(Indicator::SymbolicLink),
+                    color: Color::RGB(0, 0, 0),
+                },
+                Problem {
+                    rule: Rule::Suffix(".png".into()),
+                    color: Color::RGB(255, 0, 0),
+                },
+            ],
+            lscolors.validate_for_level(ColorLevel::Ansi256)
+        );
+
+        let problems = lscolors.validate_for_level(ColorLevel::Ansi16);
+        assert_eq!(3, problems.len());
+        assert_eq!(
+            Problem {
+                rule: Rule::Indicator(Indicator::Directory),
+                color: Color::Fixed(33),
+            },
+            problems[0]
+        );
+
+        assert!(LsColors::default()
+            .validate_for_level(ColorLevel::Ansi16)
+            .is_empty());
+    }
+
+    #[test]
+    fn layer_normal_style() {
+        let mut lscolors = LsColors::from_string("no=37:di=01:ln=36:*.png=01;35");
+        lscolors.layer_normal_style();
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::White), style_dir.foreground);
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
+
+        let style_ln = lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .unwrap();
+        assert_eq!(Some(Color::Cyan), style_ln.foreground);
+        assert_eq!(FontStyle::default(), style_ln.font_style);
+
+        let style_png = lscolors.style_for_str("image.png").unwrap();
+        assert_eq!(Some(Color::Magenta), style_png.foreground);
+        assert_eq!(FontStyle::bold(), style_png.font_style);
+
+        let style_no = lscolors.style_for_indicator(Indicator::Normal).unwrap();
+        assert_eq!(Some(Color::White), style_no.foreground);
+
+        // Without layering, only the specific style is used
+        let lscolors = LsColors::from_string("no=37:di=01");
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(None, style_dir.foreground);
+
+        // Which matches `ls --color=always -d d` (GNU coreutils 9.1): it resets between `no` and
+        // `di`, so the attributes are not combined
+        let mut out = Vec::new();
+        lscolors.write_styled(&mut out, "d", style_dir).unwrap();
+        assert_eq!(b"\x1b[0m\x1b[37m\x1b[m\x1b[01md\x1b[0m", &out[..]);
+
+        // Nothing to layer without `no`
+        let mut lscolors = LsColors::from_string("di=01");
+        lscolors.layer_normal_style();
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(None, style_dir.foreground);
+    }
+
+    #[test]
+    fn type_suffix_char() {
+        let lscolors = LsColors::default();
+        let char_for = |indicator| lscolors.type_suffix_char(indicator);
+
+        assert_eq!(Some('/'), char_for(Indicator::Directory));
+        assert_eq!(Some('/'), char_for(Indicator::StickyAndOtherWritable));
+        assert_eq!(Some('/'), char_for(Indicator::OtherWritable));
+        assert_eq!(Some('/'), char_for(Indicator::Sticky));
+        assert_eq!(Some('@'), char_for(Indicator::SymbolicLink));
+        assert_eq!(Some('@'), char_for(Indicator::OrphanedSymbolicLink));
+        assert_eq!(Some('*'), char_for(Indicator::ExecutableFile));
+        assert_eq!(Some('|'), char_for(Indicator::FIFO));
+        assert_eq!(Some('='), char_for(Indicator::Socket));
+        assert_eq!(Some('>'), char_for(Indicator::Door));
+
+        assert_eq!(None, char_for(Indicator::RegularFile));
+        assert_eq!(None, char_for(Indicator::Normal));
+        assert_eq!(None, char_for(Indicator::BlockDevice));
+        assert_eq!(None, char_for(Indicator::CharacterDevice));
+        assert_eq!(None, char_for(Indicator::MissingFile));
+    }
+
+    #[test]
+    fn type_suffix_char_for_path() {
+        let tmp_dir = temp_dir();
+        let file = create_file(tmp_dir.path().join("file"));
+        let dir = create_dir(tmp_dir.path().join("dir"));
+
+        // The style of the indicators doesn't matter
+        let lscolors = LsColors::from_string("di=00:ex=00");
+        assert_eq!(Some('/'), lscolors.type_suffix_char_for_path(&dir));
+        assert_eq!(None, lscolors.type_suffix_char_for_path(&file));
+        assert_eq!(
+            None,
+            lscolors.type_suffix_char_for_path(tmp_dir.path().join("missing"))
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            for mode in [0o755, 0o4755, 0o2755] {
+                std::fs::set_permissions(&file, std::fs::Permissions::from_mode(mode)).unwrap();
+                assert_eq!(Some('*'), lscolors.type_suffix_char_for_path(&file));
+            }
+
+            let link = tmp_dir.path().join("link");
+            create_symlink(&dir, &link);
+            assert_eq!(Some('@'), lscolors.type_suffix_char_for_path(&link));
+        }
+    }
+
+    #[test]
+    fn clone_shares_internals() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LsColors>();
+
+        let lscolors = LsColors::from_string("*.png=35");
+        let mut clone = lscolors.clone();
+        assert!(Arc::ptr_eq(&lscolors.suffixes, &clone.suffixes));
+        assert!(Arc::ptr_eq(
+            &lscolors.indicator_mapping,
+            &clone.indicator_mapping
+        ));
+
+        // Modifying a clone does not affect the original
+        clone.transform_styles(|style| Style {
+            foreground: Some(Color::Red),
+            ..style
+        });
+        assert!(!Arc::ptr_eq(&lscolors.suffixes, &clone.suffixes));
+        assert_eq!(
+            Some(Color::Magenta),
+            lscolors.style_for_str("image.png").unwrap().foreground
+        );
+        assert_eq!(
+            Some(Color::Red),
+            clone.style_for_str("image.png").unwrap().foreground
+        );
+    }
+
+    #[test]
+    fn from_env_var() {
+        let name = "LSCOLORS_TEST_FROM_ENV_VAR";
+        assert!(LsColors::from_env_var(name).is_none());
+
+        std::env::set_var(name, "di=35:*.foo=32");
+        let lscolors = LsColors::from_env_var(name).unwrap();
+        std::env::remove_var(name);
+
+        let style = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+        let style = lscolors.style_for_str("bar.foo").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+
+        // Defaults are used as the basis
+        assert!(lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .is_some());
+    }
+
+    #[test]
+    fn global() {
+        let global = LsColors::global();
+        assert!(std::ptr::eq(global, LsColors::global()));
+
+        let lscolors = LsColors::from_env().unwrap_or_default();
+        for indicator in Indicator::ALL {
+            assert_eq!(
+                lscolors.style_for_indicator(indicator),
+                global.style_for_indicator(indicator)
+            );
+        }
+        for name in ["archive.tar.gz", "image.png", "README", "main.rs"] {
+            assert_eq!(lscolors.style_for_str(name), global.style_for_str(name));
+        }
+    }
+
+    #[test]
+    fn from_string_lenient() {
+        assert_eq!(None, Indicator::from("DI"));
+        assert_eq!(
+            Some(Indicator::Directory),
+            Indicator::from_ignore_case("DI")
+        );
+        assert_eq!(
+            Some(Indicator::Directory),
+            Indicator::from_ignore_case("Di")
+        );
+        assert_eq!(
+            Some(Indicator::Directory),
+            Indicator::from_ignore_case("di")
+        );
+        assert_eq!(None, Indicator::from_ignore_case("DX"));
+
+        let input = "DI=35:Ln=36:eX=31:*.PNG=33";
+
+        let lscolors = LsColors::from_string_lenient(input);
+        let fg = |indicator| lscolors.style_for_indicator(indicator).unwrap().foreground;
+        assert_eq!(Some(Color::Magenta), fg(Indicator::Directory));
+        assert_eq!(Some(Color::Cyan), fg(Indicator::SymbolicLink));
+        assert_eq!(Some(Color::Red), fg(Indicator::ExecutableFile));
+        let style = lscolors.style_for_str("image.png").unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        // Strict by default, like GNU ls
+        let lscolors = LsColors::from_string(input);
+        let fg = |indicator| lscolors.style_for_indicator(indicator).unwrap().foreground;
+        assert_eq!(Some(Color::Blue), fg(Indicator::Directory));
+        assert_eq!(Some(Color::Green), fg(Indicator::ExecutableFile));
+    }
+
+    #[test]
+    fn transform_styles() {
+        let mut lscolors = LsColors::from_string("di=34:*.png=36");
+        lscolors.transform_styles(|style| Style {
+            font_style: FontStyle::bold(),
+            ..style
+        });
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
+        assert_eq!(Some(Color::Blue), style_dir.foreground);
+
+        let style_png = lscolors.style_for_str("image.png").unwrap();
+        assert_eq!(FontStyle::bold(), style_png.font_style);
+        assert_eq!(Some(Color::Cyan), style_png.foreground);
+
+        // Unset styles stay unset
+        assert_eq!(None, lscolors.style_for_str("image.jpg"));
+
+        lscolors.transform_styles(|style| Style {
+            foreground: style.foreground.map(|_| Color::BrightWhite),
+            ..style
+        });
+        let style_png = lscolors.style_for_str("image.png").unwrap();
+        assert_eq!(FontStyle::bold(), style_png.font_style);
+        assert_eq!(Some(Color::BrightWhite), style_png.foreground);
+    }
+
+    const DIR_COLORS: &str = "\
+# Configuration file for dircolors
+
+COLOR tty
+OPTIONS -F -T 0
+
+NORMAL 00 # no color code at all
+DIR 01;31
+LINK 01;35
+orphan 01;05;37;41
+EXEC 00;33
+
+# archives
+.tar 01;32
+*.tar.gz 01;33
+*README 04
+";
+
+    #[test]
+    fn from_reader() {
+        let lscolors = LsColors::from_reader(DIR_COLORS.as_bytes()).unwrap();
+
+        let fg = |indicator| {
+            lscolors
+                .style_for_indicator(indicator)
+                .and_then(|style| style.foreground)
+        };
+        assert_eq!(Some(Color::Red), fg(Indicator::Directory));
+        assert_eq!(Some(Color::Magenta), fg(Indicator::SymbolicLink));
+        assert_eq!(Some(Color::White), fg(Indicator::OrphanedSymbolicLink));
+        assert_eq!(Some(Color::Yellow), fg(Indicator::ExecutableFile));
+        // Defaults are preserved
+        assert_eq!(Some(Color::Magenta), fg(Indicator::Socket));
+
+        let fg = |name| {
+            lscolors
+                .style_for_str(name)
+                .and_then(|style| style.foreground)
+        };
+        assert_eq!(Some(Color::Green), fg("archive.tar"));
+        assert_eq!(Some(Color::Yellow), fg("archive.tar.gz"));
+        assert_eq!(
+            FontStyle::underline(),
+            lscolors.style_for_str("README").unwrap().font_style
+        );
+    }
+
+    #[test]
+    fn from_dircolors_for_term() {
+        let dir_colors = "\
+DIR 01;34
+TERM linux
+TERM vt100
+DIR 01;31
+EXEC 01;33
+TERM xterm*
+TERM screen*
+DIR 01;35
+";
+        let fg = |lscolors: &LsColors, indicator| {
+            lscolors
+                .style_for_indicator(indicator)
+                .and_then(|style| style.foreground)
+        };
+
+        let lscolors = LsColors::from_dircolors_for_term(dir_colors.as_bytes(), "linux").unwrap();
+        assert_eq!(Some(Color::Red), fg(&lscolors, Indicator::Directory));
+        assert_eq!(
+            Some(Color::Yellow),
+            fg(&lscolors, Indicator::ExecutableFile)
+        );
+
+        let lscolors =
+            LsColors::from_dircolors_for_term(dir_colors.as_bytes(), "xterm-256color").unwrap();
+        assert_eq!(Some(Color::Magenta), fg(&lscolors, Indicator::Directory));
+        assert_eq!(Some(Color::Green), fg(&lscolors, Indicator::ExecutableFile));
+
+        let lscolors = LsColors::from_dircolors_for_term(dir_colors.as_bytes(), "dumb").unwrap();
+        assert_eq!(Some(Color::Blue), fg(&lscolors, Indicator::Directory));
+        assert_eq!(Some(Color::Green), fg(&lscolors, Indicator::ExecutableFile));
+    }
+
+    #[test]
+    fn from_dircolors_file() {
+        let tmp_dir = temp_dir();
+        let path = tmp_dir.path().join("dircolors");
+        fs::write(&path, DIR_COLORS).unwrap();
+
+        let lscolors = LsColors::from_dircolors_file(&path).unwrap();
+        assert_eq!(
+            Some(Color::Red),
+            lscolors
+                .style_for_indicator(Indicator::Directory)
+                .unwrap()
+                .foreground
+        );
+
+        assert!(LsColors::from_dircolors_file(tmp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn write_styled() {
+        let write = |lscolors: &LsColors, text, style: Option<&Style>| {
+            let mut out = Vec::new();
+            lscolors
+                .write_styled(&mut out, text, style.unwrap_or(&Style::default()))
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Compared to the output of `ls --color=always -d <file>` (GNU coreutils 9.1)
+        let lscolors = LsColors::from_string("rs=0:di=01;34:*.gz=01;31");
+        let di = lscolors.style_for_indicator(Indicator::Directory);
+        assert_eq!("\x1b[0m\x1b[01;34mdir\x1b[0m", write(&lscolors, "dir", di));
+        let gz = lscolors.style_for_str("a.gz");
+        assert_eq!(
+            "\x1b[0m\x1b[01;31ma.gz\x1b[0m",
+            write(&lscolors, "a.gz", gz)
+        );
+
+        let lscolors = LsColors::from_string("no=37:di=01;34:ec=E");
+        let di = lscolors.style_for_indicator(Indicator::Directory);
+        assert_eq!(
+            "E\x1b[37m\x1b[m\x1b[01;34mdirE",
+            write(&lscolors, "dir", di)
+        );
+        assert_eq!("E\x1b[37mfileE", write(&lscolors, "file", None));
+
+        let mut lscolors = LsColors::empty();
+        lscolors.extend([(
+            Indicator::Directory,
+            Style::from_ansi_sequence("01;34").unwrap(),
+        )]);
+        let di = lscolors.style_for_indicator(Indicator::Directory);
+        assert_eq!("\x1b[0m\x1b[01;34mdir\x1b[0m", write(&lscolors, "dir", di));
+
+        let lscolors = LsColors::from_string("di=01;34:lc=<:rc=>");
+        let di = lscolors.style_for_indicator(Indicator::Directory);
+        assert_eq!("<0><01;34>dir<0>", write(&lscolors, "dir", di));
+
+        // Nothing to style
+        assert_eq!("file", write(&lscolors, "file", None));
+    }
+
+    #[test]
+    fn write_path() {
+        use std::path::MAIN_SEPARATOR;
+
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        let tmp_file = create_file(tmp_dir.join("test-file.png"));
+
+        let lscolors = LsColors::from_string("di=01;34:*.png=04;36:no=0");
+
+        let relative = PathBuf::from(".").join("test-dir").join("test-file.png");
+        for path in [
+            tmp_file.as_path(),
+            tmp_dir.as_path(),
+            relative.as_path(),
+            Path::new("missing"),
+        ] {
+            let mut out = Vec::new();
+            lscolors.write_path(&mut out, path).unwrap();
+
+            let expected: String = lscolors
+                .style_for_path_components(path)
+                .map(|(component, style)| {
+                    let component = component.to_string_lossy().into_owned();
+                    style
+                        .copied()
+                        .unwrap_or_default()
+                        .paint(component)
+                        .to_string()
+                })
+                .collect();
+            assert_eq!(expected, String::from_utf8(out).unwrap());
+        }
+
+        let mut out = Vec::new();
+        lscolors.write_path(&mut out, &tmp_file).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&format!("\x1b[01;34mtest-dir{}\x1b[0m", MAIN_SEPARATOR)));
+        assert!(out.ends_with("\x1b[04;36mtest-file.png\x1b[0m"));
+    }
+
+    #[test]
+    fn write_path_basename() {
+        use crate::os_str_bytes;
+        use std::path::MAIN_SEPARATOR;
+
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        let tmp_file = create_file(tmp_dir.join("test-file.png"));
+
+        let lscolors = LsColors::from_string("di=01;34:*.png=04;36");
+
+        let mut out = Vec::new();
+        lscolors.write_path_basename(&mut out, &tmp_file).unwrap();
+        let mut expected = os_str_bytes(tmp_dir.as_os_str()).into_owned();
+        expected.extend_from_slice(MAIN_SEPARATOR.to_string().as_bytes());
+        expected.extend_from_slice(b"\x1b[04;36mtest-file.png\x1b[0m");
+        assert_eq!(expected, out);
+
+        // The last component is styled by its own file type
+        let mut out = Vec::new();
+        lscolors.write_path_basename(&mut out, &tmp_dir).unwrap();
+        assert!(out.ends_with(b"\x1b[01;34mtest-dir\x1b[0m"));
+
+        let mut out = Vec::new();
+        lscolors
+            .write_path_basename(&mut out, Path::new("missing"))
+            .unwrap();
+        assert_eq!(b"missing", &out[..]);
+    }
+
+    #[test]
+    fn write_styled_with_clear() {
+        let write = |lscolors: &LsColors, text, style, start_col, line_length| {
+            let mut out = Vec::new();
+            lscolors
+                .write_styled_with_clear(&mut out, text, style, start_col, line_length)
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let lscolors = LsColors::from_string("rs=0:di=01;34:cl=\x1b[K");
+        let di = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+
+        // Fits into the line
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m",
+            write(&lscolors, "dir", di, 0, 80)
+        );
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m",
+            write(&lscolors, "dir", di, 77, 80)
+        );
+
+        // Wraps onto the next line
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m\x1b[K",
+            write(&lscolors, "dir", di, 78, 80)
+        );
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m\x1b[K",
+            write(&lscolors, "dir", di, 0, 2)
+        );
+
+        // Unknown line length
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m",
+            write(&lscolors, "dir", di, 78, 0)
+        );
+
+        // Not colored
+        let plain = Style::default();
+        assert_eq!("file", write(&lscolors, "file", &plain, 78, 80));
+
+        // Custom and default `cl`
+        let lscolors = LsColors::from_string("rs=0:di=01;34:cl=<CL>");
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m<CL>",
+            write(&lscolors, "dir", di, 78, 80)
+        );
+        let lscolors = LsColors::from_string("rs=0:di=01;34");
+        assert_eq!(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m\x1b[K",
+            write(&lscolors, "dir", di, 78, 80)
+        );
+    }
+
+    #[test]
+    fn executable_extensions() {
+        let tmp_dir = temp_dir();
+        let missing = tmp_dir.path().join("foo.sh");
+
+        // Off by default
+        let lscolors = LsColors::from_string("ex=01;32:*.py=33");
+        assert_eq!(
+            Indicator::RegularFile,
+            lscolors.explain_for_path(&missing).indicator
+        );
+
+        let lscolors = lscolors.executable_extensions(&["sh", ".py"]);
+        assert_eq!(
+            Indicator::ExecutableFile,
+            lscolors.explain_for_path(&missing).indicator
+        );
+        assert_eq!(
+            Some(Color::Green),
+            lscolors
+                .style_for_path_with_metadata(&missing, None)
+                .unwrap()
+                .foreground
+        );
+        assert_eq!(
+            Indicator::ExecutableFile,
+            lscolors
+                .explain_for_path(tmp_dir.path().join("BUILD.SH"))
+                .indicator
+        );
+        assert_eq!(
+            Indicator::RegularFile,
+            lscolors
+                .explain_for_path(tmp_dir.path().join("foo.shx"))
+                .indicator
+        );
+
+        // Suffix rules take precedence
+        let style = lscolors
+            .style_for_path(tmp_dir.path().join("foo.py"))
+            .unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        // Metadata takes precedence
+        let file = create_file(tmp_dir.path().join("bar.sh"));
+        assert_eq!(
+            Indicator::RegularFile,
+            lscolors.explain_for_path(&file).indicator
+        );
+
+        let lscolors = lscolors.executable_extensions(&[]);
+        assert_eq!(
+            Indicator::RegularFile,
+            lscolors.explain_for_path(&missing).indicator
+        );
+    }
+
+    #[test]
+    fn style_for_path_with_file_type() {
+        let lscolors = LsColors::from_string("di=34:fi=35:*.txt=36:ex=31:tw=32");
+
+        let tmp_dir = temp_dir();
+        let dir_type = tmp_dir.path().symlink_metadata().unwrap().file_type();
+
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).unwrap();
+        let file_type = file_path.symlink_metadata().unwrap().file_type();
+
+        // The paths do not exist, so only the file type is used
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors
+                .style_for_path_with_file_type("does/not/exist", Some(dir_type))
+                .and_then(|style| style.foreground)
+        );
+        assert_eq!(
+            Some(Color::Magenta),
+            lscolors
+                .style_for_path_with_file_type("does/not/exist", Some(file_type))
+                .and_then(|style| style.foreground)
+        );
+        assert_eq!(
+            Some(Color::Cyan),
+            lscolors
+                .style_for_path_with_file_type("does/not/exist.txt", Some(file_type))
+                .and_then(|style| style.foreground)
+        );
+        assert_eq!(
+            Some(Color::Cyan),
+            lscolors
+                .style_for_path_with_file_type("does/not/exist.txt", None)
+                .and_then(|style| style.foreground)
+        );
+    }
+
+    #[test]
+    fn symlink_target_exists() {
+        use crate::Colorable;
+        use std::ffi::OsString;
+        use std::fs::{FileType, Metadata};
+
+        struct Link {
+            path: PathBuf,
+            target_exists: bool,
+        }
+
+        impl Colorable for Link {
+            fn path(&self) -> PathBuf {
+                self.path.clone()
+            }
+
+            fn file_name(&self) -> OsString {
+                self.path.file_name().unwrap().to_owned()
+            }
+
+            fn file_type(&self) -> Option<FileType> {
+                self.path.symlink_metadata().ok().map(|m| m.file_type())
+            }
+
+            fn metadata(&self) -> Option<Metadata> {
+                self.path.symlink_metadata().ok()
+            }
+
+            fn symlink_target_exists(&self) -> Option<bool> {
+                Some(self.target_exists)
+            }
+        }
+
+        let tmp_dir = temp_dir();
+        let target = tmp_dir.path().join("does-not-exist");
+        let path = tmp_dir.path().join("link");
+        create_symlink(&target, &path);
+
+        let lscolors = LsColors::from_string("ln=34:or=31");
+        assert_eq!(
+            Some(Color::Red),
+            lscolors.style_for_path(&path).and_then(|s| s.foreground)
+        );
+
+        // The reported status is used instead of checking the file system
+        let link = Link {
+            path: path.clone(),
+            target_exists: true,
+        };
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors.style_for(&link).and_then(|s| s.foreground)
+        );
+
+        let link = Link {
+            path,
+            target_exists: false,
+        };
+        assert_eq!(
+            Some(Color::Red),
+            lscolors.style_for(&link).and_then(|s| s.foreground)
+        );
+    }
+
+    #[test]
+    fn from_string_trailing_newline() {
+        let lscolors = LsColors::from_string("di=01;34:*.gz=01;31\n");
+        let style = lscolors.style_for_str("a.gz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+
+        let lscolors = LsColors::from_string("di=01;34\n*.gz=01;31\n\n");
+        let style = lscolors.style_for_str("a.gz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        let style = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+    }
+
+    #[test]
+    fn from_string_comments() {
+        let lscolors = LsColors::from_string("# my colors:*.gz=01;31:#*.rs=01;32:");
+        let style = lscolors.style_for_str("a.gz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        assert_eq!(None, lscolors.style_for_str("main.rs"));
+
+        let lscolors = LsColors::from_string("# archives\n*.gz=01;31\n  # sources\n*.rs=01;32");
+        let style = lscolors.style_for_str("main.rs").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+    }
+
+    #[test]
+    fn from_string_whitespace() {
+        let lscolors = LsColors::from_string(" di = 01;34 : *.gz=01;31 :: *.rs =\t32 ");
+        let style = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+        let style = lscolors.style_for_str("a.gz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        let style = lscolors.style_for_str("main.rs").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+    }
+
+    #[test]
+    fn all_indicators() {
+        let all = Indicator::all();
+        assert_eq!(26, all.len());
+        assert_eq!(Some(&Indicator::Normal), all.first());
+        assert_eq!(Some(&Indicator::System), all.last());
+
+        // Every indicator of GNU `ls` round-trips through its code
+        for (i, indicator) in all.iter().enumerate() {
+            assert_eq!(i, indicator.index());
+            match indicator.code() {
+                Some(code) => assert_eq!(Some(*indicator), Indicator::from(code)),
+                None => assert!(matches!(indicator, Indicator::Hidden | Indicator::System)),
+            }
+        }
+        assert_eq!(None, Indicator::from("hidden"));
+        assert_eq!(None, Indicator::from_ignore_case("SYSTEM"));
+
+        // The indices of the indicators of GNU `ls` are unchanged
+        assert_eq!(18, Indicator::MultipleHardLinks.index());
+        assert_eq!(23, Indicator::ClearLine.index());
+    }
+
+    #[test]
+    fn style_for_indicator_str() {
+        let lscolors = LsColors::from_string("or=40;31:di=34");
+
+        assert_eq!(
+            lscolors.style_for_indicator(Indicator::MissingFile),
+            lscolors.style_for_indicator_str("mi")
+        );
+        let style = lscolors.style_for_indicator_str("mi").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        assert_eq!(Some(Color::Black), style.background);
+
+        let style = lscolors.style_for_indicator_str("di").unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+
+        assert_eq!(None, lscolors.style_for_indicator_str("xx"));
+        assert_eq!(None, lscolors.style_for_indicator_str("*.gz"));
+    }
+
+    #[test]
+    fn fallback_policy() {
+        use crate::FallbackPolicy;
+
+        let foreground = |lscolors: &LsColors, indicator| {
+            lscolors
+                .style_for_indicator(indicator)
+                .and_then(|style| style.foreground)
+        };
+
+        let lscolors = LsColors::from_string("no=37:ln=36:or=31:di=34");
+        assert_eq!(
+            Some(Color::Red),
+            foreground(&lscolors, Indicator::MissingFile)
+        );
+
+        let gnu = lscolors.clone().fallback_policy(FallbackPolicy::gnu());
+        for indicator in Indicator::all() {
+            assert_eq!(
+                lscolors.style_for_indicator(*indicator),
+                gnu.style_for_indicator(*indicator)
+            );
+        }
+
+        let none = lscolors.clone().fallback_policy(FallbackPolicy::none());
+        assert_eq!(None, foreground(&none, Indicator::MissingFile));
+        assert_eq!(
+            Some(Color::White),
+            foreground(&lscolors, Indicator::Capabilities)
+        );
+        assert_eq!(None, foreground(&none, Indicator::Capabilities));
+        assert_eq!(
+            Some(Color::Red),
+            foreground(&none, Indicator::OrphanedSymbolicLink)
+        );
+
+        // Orphaned symlinks to directories, without `or`
+        let lscolors = LsColors::from_string("no=37:ln=36:or=0:di=34");
+        assert_eq!(
+            Some(Color::Cyan),
+            foreground(&lscolors, Indicator::OrphanedSymbolicLink)
+        );
+        assert_eq!(
+            Some(Color::White),
+            foreground(&lscolors, Indicator::MissingFile)
+        );
+        let custom = lscolors.fallback_policy(
+            FallbackPolicy::gnu()
+                .with_fallback(Indicator::OrphanedSymbolicLink, Some(Indicator::Directory))
+                .with_fallback(Indicator::MissingFile, None)
+                .with_normal_fallback(false),
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            foreground(&custom, Indicator::OrphanedSymbolicLink)
+        );
+        assert_eq!(None, foreground(&custom, Indicator::MissingFile));
+    }
+
+    #[test]
+    fn unicode_case_insensitive_suffixes() {
+        let lscolors = LsColors::from_string("*.DatENsätze=31:*.ÄRCHIV=32:*.txt=33");
+        assert!(lscolors.style_for_str("daten.datensätze").is_some());
+        assert_eq!(None, lscolors.style_for_str("DATEN.DATENSÄTZE"));
+        assert_eq!(None, lscolors.style_for_str("daten.ärchiv"));
+
+        let lscolors = lscolors.unicode_case_insensitive_suffixes(true);
+        let foreground = |s| lscolors.style_for_str(s).and_then(|style| style.foreground);
+        assert_eq!(Some(Color::Red), foreground("daten.datensätze"));
+        assert_eq!(Some(Color::Red), foreground("DATEN.DATENSÄTZE"));
+        assert_eq!(Some(Color::Green), foreground("daten.ärchiv"));
+        assert_eq!(Some(Color::Yellow), foreground("NOTES.TXT"));
+        assert_eq!(None, foreground("daten.datensatze"));
+
+        // Different capitalizations with different styles are still matched case-sensitively
+        let lscolors =
+            LsColors::from_string("*.Ä=31:*.ä=32").unicode_case_insensitive_suffixes(true);
+        let foreground = |s| lscolors.style_for_str(s).and_then(|style| style.foreground);
+        assert_eq!(Some(Color::Red), foreground("a.Ä"));
+        assert_eq!(Some(Color::Green), foreground("a.ä"));
+    }
+
+    #[test]
+    fn suffix_match_modes() {
+        use crate::SuffixMatch;
+
+        let foreground =
+            |lscolors: &LsColors, s| lscolors.style_for_str(s).and_then(|style| style.foreground);
+
+        for (colors, last_wins) in [
+            ("*.gz=31:*.tar.gz=32", Color::Green),
+            ("*.tar.gz=32:*.gz=31", Color::Red),
+        ] {
+            let lscolors = LsColors::from_string(colors);
+            assert_eq!(Some(last_wins), foreground(&lscolors, "foo.tar.gz"));
+            assert_eq!(Some(Color::Red), foreground(&lscolors, "foo.gz"));
+
+            let lscolors = lscolors.suffix_match(SuffixMatch::Longest);
+            assert_eq!(Some(Color::Green), foreground(&lscolors, "foo.tar.gz"));
+            assert_eq!(Some(Color::Red), foreground(&lscolors, "foo.gz"));
+        }
+
+        // Ties are broken by the declaration order
+        let lscolors =
+            LsColors::from_string("*.gz=31:*.GZ=31:*.gz=32").suffix_match(SuffixMatch::Longest);
+        assert_eq!(Some(Color::Green), foreground(&lscolors, "foo.gz"));
+
+        // Case-sensitive and case-insensitive matches are compared by length as well
+        let lscolors =
+            LsColors::from_string("*.tar.gz=32:*.GZ=33:*.gz=31").suffix_match(SuffixMatch::Longest);
+        assert_eq!(Some(Color::Green), foreground(&lscolors, "foo.tar.gz"));
+        assert_eq!(Some(Color::Yellow), foreground(&lscolors, "foo.GZ"));
+        assert_eq!(Some(Color::Red), foreground(&lscolors, "foo.gz"));
+
+        // Versioned suffixes
+        let lscolors = LsColors::from_string("*.so.*=32:*.1=31").suffix_match(SuffixMatch::Longest);
+        assert_eq!(Some(Color::Green), foreground(&lscolors, "libfoo.so.1"));
+    }
+
+    #[test]
+    fn max_suffix_len() {
+        assert_eq!(0, LsColors::empty().max_suffix_len());
+        assert_eq!(7, LsColors::from_string("*.tar.gz=01;31").max_suffix_len());
+        assert_eq!(
+            7,
+            LsColors::from_string("*.gz=01;31:*.tar.gz=01;33:*.zip=01;31").max_suffix_len()
+        );
+
+        // Multi-byte characters count as multiple bytes
+        assert_eq!(3, LsColors::from_string("*.ä=01;31").max_suffix_len());
+    }
+
+    #[test]
+    fn matched_suffix_for_str() {
+        let lscolors = LsColors::from_string("*.gz=01;31:*.tar.gz=01;33:*.so.*=32:*.old=0");
+
+        let suffix = lscolors.matched_suffix_for_str("foo.tar.gz").unwrap();
+        assert_eq!(b".tar.gz".to_vec(), suffix.pattern());
+        assert_eq!(7, suffix.matched_len());
+        assert_eq!(lscolors.style_for_str("foo.tar.gz"), suffix.style());
+        assert_eq!(Some(Color::Yellow), suffix.style().unwrap().foreground);
+
+        let suffix = lscolors.matched_suffix_for_str("foo.GZ").unwrap();
+        assert_eq!(b".gz".to_vec(), suffix.pattern());
+        assert_eq!(3, suffix.matched_len());
+
+        let suffix = lscolors.matched_suffix_for_str("libfoo.so.1.2").unwrap();
+        assert_eq!(b".so.*".to_vec(), suffix.pattern());
+        assert_eq!(7, suffix.matched_len());
+
+        // Matching suffix without a style
+        let suffix = lscolors.matched_suffix_for_str("foo.old").unwrap();
+        assert_eq!(b".old".to_vec(), suffix.pattern());
+        assert_eq!(None, suffix.style());
+
+        assert_eq!(None, lscolors.matched_suffix_for_str("foo.txt"));
+    }
+
+    #[test]
+    fn from_dircolors_output() {
+        let bourne = "LS_COLORS='rs=0:di=01;34:ln=01;36:*.tar=01;31:';\nexport LS_COLORS\n";
+        let csh = "setenv LS_COLORS 'rs=0:di=01;34:ln=01;36:*.tar=01;31:'\n";
+
+        for output in [bourne, csh] {
+            let lscolors = LsColors::from_dircolors_output(output).unwrap();
+            let style = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+            assert_eq!(Some(Color::Blue), style.foreground);
+            let style = lscolors.style_for_str("archive.tar").unwrap();
+            assert_eq!(Some(Color::Red), style.foreground);
+        }
+
+        assert!(LsColors::from_dircolors_output("DIR 01;34\n").is_none());
+    }
+
+    #[test]
+    fn code_accessors() {
+        let lscolors = LsColors::from_string(r"lc=\x1b[:rc=m:rs=0:ec=:cl=");
+        assert_eq!(Some("\x1b["), lscolors.left_code());
+        assert_eq!(Some("m"), lscolors.right_code());
+        assert_eq!(Some("0"), lscolors.reset_code());
+        assert_eq!(Some(""), lscolors.end_code());
+        assert_eq!(Some(""), lscolors.clear_line());
+
+        let lscolors = LsColors::from_string("lc=<:rc=>:rs=RESET:ec=END:cl=CLEAR");
+        assert_eq!(Some("<"), lscolors.left_code());
+        assert_eq!(Some(">"), lscolors.right_code());
+        assert_eq!(Some("RESET"), lscolors.reset_code());
+        assert_eq!(Some("END"), lscolors.end_code());
+        assert_eq!(Some("CLEAR"), lscolors.clear_line());
+
+        let lscolors = LsColors::default();
+        assert_eq!(Some("\x1b[K"), lscolors.clear_line());
+        assert_eq!(None, lscolors.end_code());
+        assert_eq!(None, LsColors::empty().left_code());
+    }
+
+    #[test]
+    fn codes() {
+        let lscolors = LsColors::default();
+        assert_eq!(
+            Some("\x1b["),
+            lscolors.code_for_indicator(Indicator::LeftCode)
+        );
+        assert_eq!(Some("m"), lscolors.code_for_indicator(Indicator::RightCode));
+        assert_eq!(
+            Some("\x1b[K"),
+            lscolors.code_for_indicator(Indicator::ClearLine)
+        );
+        assert_eq!(Some("0"), lscolors.code_for_indicator(Indicator::Reset));
+        assert_eq!(None, lscolors.code_for_indicator(Indicator::EndCode));
+        assert_eq!(None, lscolors.code_for_indicator(Indicator::Directory));
+
+        let lscolors = LsColors::from_string(r"lc=\e[:rc=^[m:ec=\033[0m:di=01;34");
+        assert_eq!(
+            Some("\x1b["),
+            lscolors.code_for_indicator(Indicator::LeftCode)
+        );
+        assert_eq!(
+            Some("\x1bm"),
+            lscolors.code_for_indicator(Indicator::RightCode)
+        );
+        assert_eq!(
+            Some("\x1b[0m"),
+            lscolors.code_for_indicator(Indicator::EndCode)
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors
+                .style_for_indicator(Indicator::Directory)
+                .unwrap()
+                .foreground
+        );
+    }
+
+    #[test]
+    fn indicator_index() {
+        for (i, indicator) in Indicator::ALL.iter().enumerate() {
+            assert_eq!(i, indicator.index());
+            if let Some(code) = indicator.code() {
+                assert_eq!(Some(*indicator), Indicator::from(code));
+            }
+        }
+    }
+
+    #[test]
+    fn style_for_indicator_fallback() {
+        let lscolors = LsColors::from_string("no=31:fi=32:di=33:ln=34:ex=0:tw=0:or=0");
+        let fg = |indicator| {
+            lscolors
+                .style_for_indicator(indicator)
+                .and_then(|style| style.foreground)
+        };
+
+        assert_eq!(Some(Color::Green), fg(Indicator::ExecutableFile));
+        assert_eq!(Some(Color::Green), fg(Indicator::MultipleHardLinks));
+        assert_eq!(Some(Color::Yellow), fg(Indicator::StickyAndOtherWritable));
+        assert_eq!(Some(Color::Blue), fg(Indicator::OrphanedSymbolicLink));
+        assert_eq!(Some(Color::Red), fg(Indicator::MissingFile));
+        assert_eq!(Some(Color::Red), fg(Indicator::Capabilities));
+
+        assert!(!lscolors.has_color_for(Indicator::ExecutableFile));
+        assert!(lscolors.has_color_for(Indicator::RegularFile));
+    }
+
     #[test]
     fn file_suffix_case() {
         let assert_bold_fg_magenta = |style: Option<&Style>| {
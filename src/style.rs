@@ -5,10 +5,13 @@
 #[cfg(feature = "nu-ansi-term")]
 use nu_ansi_term;
 use std::collections::VecDeque;
+use std::fmt;
 
 /// A `Color` can be one of the pre-defined ANSI colors (`Red`, `Green`, ..),
-/// a 8-bit ANSI color (`Fixed(u8)`) or a 24-bit color (`RGB(u8, u8, u8)`).
+/// a 8-bit ANSI color (`Fixed(u8)`), a 24-bit color (`RGB(u8, u8, u8)`)
+/// or the default color of the terminal (`Default`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Black,
     Red,
@@ -28,13 +31,155 @@ pub enum Color {
     BrightWhite,
     Fixed(u8),
     RGB(u8, u8, u8),
+    /// The default color of the terminal (SGR `39`, `49` and `59`).
+    Default,
+}
+
+/// The range of colors that a terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorLevel {
+    /// The 16 basic ANSI colors (`Red`, `BrightRed`, ...).
+    Ansi16,
+    /// The 256-color palette (`Fixed(u8)`).
+    Ansi256,
+    /// 24-bit colors (`RGB(u8, u8, u8)`).
+    TrueColor,
 }
 
 impl Color {
+    /// Get the index of the 16 basic colors in the 256-color palette.
+    fn ansi_index(&self) -> Option<u8> {
+        match self {
+            Color::Black => Some(0),
+            Color::Red => Some(1),
+            Color::Green => Some(2),
+            Color::Yellow => Some(3),
+            Color::Blue => Some(4),
+            Color::Magenta => Some(5),
+            Color::Cyan => Some(6),
+            Color::White => Some(7),
+            Color::BrightBlack => Some(8),
+            Color::BrightRed => Some(9),
+            Color::BrightGreen => Some(10),
+            Color::BrightYellow => Some(11),
+            Color::BrightBlue => Some(12),
+            Color::BrightMagenta => Some(13),
+            Color::BrightCyan => Some(14),
+            Color::BrightWhite => Some(15),
+            Color::Fixed(_) | Color::RGB(..) | Color::Default => None,
+        }
+    }
+
+    /// Encode the color as SGR parameters, where `base` is 30 for the foreground and 40 for the
+    /// background color.
+    fn write_ansi_sequence<W: fmt::Write>(&self, out: &mut W, base: u8) -> fmt::Result {
+        match *self {
+            Color::Fixed(n) => write!(out, "{};5;{}", base + 8, n),
+            Color::RGB(r, g, b) => write!(out, "{};2;{};{};{}", base + 8, r, g, b),
+            Color::Default => write!(out, "{}", base + 9),
+            color => match color.ansi_index() {
+                Some(i @ 0..=7) => write!(out, "{}", base + i),
+                Some(i) => write!(out, "{}", base + 60 + i - 8),
+                None => unreachable!(),
+            },
+        }
+    }
+
+    /// Encode the color as SGR parameters for the underline color, see
+    /// [`Style::underline_ansi_sequence`].
+    fn write_underline_sequence<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        match *self {
+            Color::RGB(r, g, b) => write!(out, "58;2;{};{};{}", r, g, b),
+            Color::Fixed(n) => write!(out, "58;5;{}", n),
+            Color::Default => out.write_str("59"),
+            color => write!(out, "58;5;{}", color.ansi_index().unwrap_or_default()),
+        }
+    }
+
+    /// Get the minimal [ColorLevel] that a terminal needs to display this color.
+    pub fn level(&self) -> ColorLevel {
+        match self {
+            Color::Fixed(_) => ColorLevel::Ansi256,
+            Color::RGB(..) => ColorLevel::TrueColor,
+            _ => ColorLevel::Ansi16,
+        }
+    }
+
+    /// Approximate the color by the nearest of the 16 basic ANSI colors, for terminals that can't
+    /// display `Fixed` or `RGB` colors. The distance between two colors is the squared Euclidean
+    /// distance of their RGB values, where palette colors are resolved using the default xterm
+    /// palette (see the `TryFrom<Color>` implementation for `[u8; 3]`). The basic colors and the
+    /// default color are returned unchanged.
+    ///
+    /// ```
+    /// use lscolors::Color;
+    ///
+    /// assert_eq!(Color::BrightRed, Color::Fixed(196).approximate_ansi16());
+    /// assert_eq!(Color::Blue, Color::RGB(10, 10, 200).approximate_ansi16());
+    /// ```
+    pub fn approximate_ansi16(&self) -> Color {
+        let Ok([r, g, b]) = <[u8; 3]>::try_from(*self) else {
+            return *self;
+        };
+        if self.level() == ColorLevel::Ansi16 {
+            return *self;
+        }
+
+        ANSI_COLORS
+            .into_iter()
+            .zip(ANSI_PALETTE)
+            .min_by_key(|&(_, rgb)| rgb_distance([r, g, b], rgb))
+            .map_or(*self, |(color, _)| color)
+    }
+
+    /// Convert an `RGB` color to the nearest color of the 256-color palette, for terminals that
+    /// can't display 24-bit colors. The candidates are the nearest color of the 6×6×6 color cube
+    /// (`Fixed(16)` to `Fixed(231)`) and the nearest gray of the grayscale ramp (`Fixed(232)` to
+    /// `Fixed(255)`), compared by the same distance as in [`Color::approximate_ansi16`]. All
+    /// other colors are returned unchanged.
+    ///
+    /// ```
+    /// use lscolors::Color;
+    ///
+    /// assert_eq!(Color::Fixed(196), Color::RGB(255, 0, 0).to_fixed256());
+    /// assert_eq!(Color::Fixed(244), Color::RGB(128, 128, 128).to_fixed256());
+    /// ```
+    pub fn to_fixed256(&self) -> Color {
+        let Color::RGB(r, g, b) = *self else {
+            return *self;
+        };
+
+        let cube_index = |v: u8| {
+            (0..6)
+                .min_by_key(|&i| (i32::from(fixed_to_rgb(16 + i * 36)[0]) - i32::from(v)).abs())
+                .unwrap_or(0)
+        };
+        let cube = 16 + 36 * cube_index(r) + 6 * cube_index(g) + cube_index(b);
+
+        let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+        let gray = 232 + ((average.saturating_sub(3) / 10).min(23) as u8);
+
+        [cube, gray]
+            .into_iter()
+            .min_by_key(|&n| rgb_distance([r, g, b], fixed_to_rgb(n)))
+            .map_or(*self, Color::Fixed)
+    }
+
     /// Convert to a `ansi_term::Color` (if the `ansi_term` feature is enabled).
+    ///
+    /// `ansi_term` has no default color, so `Default` is converted to `White`. Use
+    /// [`Color::try_to_ansi_term_color`] to detect it instead.
     #[cfg(feature = "ansi_term")]
     pub fn to_ansi_term_color(&self) -> ansi_term::Color {
-        match self {
+        self.try_to_ansi_term_color()
+            .unwrap_or(ansi_term::Color::White)
+    }
+
+    /// Like [`Color::to_ansi_term_color`], but returns `None` for `Default` (like in
+    /// [`Style::to_ansi_term_style`], where the color is left unset).
+    #[cfg(feature = "ansi_term")]
+    pub fn try_to_ansi_term_color(&self) -> Option<ansi_term::Color> {
+        Some(match self {
             Color::RGB(r, g, b) => ansi_term::Color::RGB(*r, *g, *b),
             Color::Fixed(n) => ansi_term::Color::Fixed(*n),
             Color::Black => ansi_term::Color::Black,
@@ -56,6 +201,25 @@ impl Color {
             Color::BrightMagenta => ansi_term::Color::Fixed(13),
             Color::BrightCyan => ansi_term::Color::Fixed(14),
             Color::BrightWhite => ansi_term::Color::Fixed(15),
+
+            Color::Default => return None,
+        })
+    }
+
+    /// Convert from a `ansi_term::Color` (if the `ansi_term` feature is enabled).
+    #[cfg(feature = "ansi_term")]
+    pub fn from_ansi_term_color(color: &ansi_term::Color) -> Color {
+        match *color {
+            ansi_term::Color::Black => Color::Black,
+            ansi_term::Color::Red => Color::Red,
+            ansi_term::Color::Green => Color::Green,
+            ansi_term::Color::Yellow => Color::Yellow,
+            ansi_term::Color::Blue => Color::Blue,
+            ansi_term::Color::Purple => Color::Magenta,
+            ansi_term::Color::Cyan => Color::Cyan,
+            ansi_term::Color::White => Color::White,
+            ansi_term::Color::Fixed(n) => Color::Fixed(n),
+            ansi_term::Color::RGB(r, g, b) => Color::RGB(r, g, b),
         }
     }
 
@@ -84,6 +248,36 @@ impl Color {
             Color::BrightMagenta => nu_ansi_term::Color::Fixed(13),
             Color::BrightCyan => nu_ansi_term::Color::Fixed(14),
             Color::BrightWhite => nu_ansi_term::Color::Fixed(15),
+
+            Color::Default => nu_ansi_term::Color::Default,
+        }
+    }
+
+    /// Convert from a `nu_ansi_term::Color` (if the `nu_ansi_term` or `gnu_legacy` feature is enabled).
+    #[cfg(any(feature = "nu-ansi-term", feature = "gnu_legacy"))]
+    pub fn from_nu_ansi_term_color(color: &nu_ansi_term::Color) -> Color {
+        match *color {
+            nu_ansi_term::Color::Black => Color::Black,
+            nu_ansi_term::Color::Red => Color::Red,
+            nu_ansi_term::Color::Green => Color::Green,
+            nu_ansi_term::Color::Yellow => Color::Yellow,
+            nu_ansi_term::Color::Blue => Color::Blue,
+            nu_ansi_term::Color::Purple | nu_ansi_term::Color::Magenta => Color::Magenta,
+            nu_ansi_term::Color::Cyan => Color::Cyan,
+            nu_ansi_term::Color::White => Color::White,
+            nu_ansi_term::Color::DarkGray => Color::BrightBlack,
+            nu_ansi_term::Color::LightRed => Color::BrightRed,
+            nu_ansi_term::Color::LightGreen => Color::BrightGreen,
+            nu_ansi_term::Color::LightYellow => Color::BrightYellow,
+            nu_ansi_term::Color::LightBlue => Color::BrightBlue,
+            nu_ansi_term::Color::LightPurple | nu_ansi_term::Color::LightMagenta => {
+                Color::BrightMagenta
+            }
+            nu_ansi_term::Color::LightCyan => Color::BrightCyan,
+            nu_ansi_term::Color::LightGray => Color::BrightWhite,
+            nu_ansi_term::Color::Fixed(n) => Color::Fixed(n),
+            nu_ansi_term::Color::Rgb(r, g, b) => Color::RGB(r, g, b),
+            nu_ansi_term::Color::Default => Color::Default,
         }
     }
 
@@ -113,6 +307,7 @@ impl Color {
             Color::BrightMagenta => crossterm::style::Color::Magenta,
             Color::BrightCyan => crossterm::style::Color::Cyan,
             Color::BrightWhite => crossterm::style::Color::White,
+            Color::Default => crossterm::style::Color::Reset,
         }
     }
 
@@ -144,12 +339,188 @@ impl Color {
             }
             Color::BrightCyan => owo_colors::DynColors::Ansi(owo_colors::AnsiColors::BrightCyan),
             Color::BrightWhite => owo_colors::DynColors::Ansi(owo_colors::AnsiColors::BrightWhite),
+
+            Color::Default => owo_colors::DynColors::Ansi(owo_colors::AnsiColors::Default),
         }
     }
+
+    /// Convert to a `yansi::Color` (if the `yansi` feature is enabled).
+    #[cfg(feature = "yansi")]
+    pub fn to_yansi_color(&self) -> yansi::Color {
+        match self {
+            Color::RGB(r, g, b) => yansi::Color::Rgb(*r, *g, *b),
+            Color::Fixed(n) => yansi::Color::Fixed(*n),
+            Color::Black => yansi::Color::Black,
+            Color::Red => yansi::Color::Red,
+            Color::Green => yansi::Color::Green,
+            Color::Yellow => yansi::Color::Yellow,
+            Color::Blue => yansi::Color::Blue,
+            Color::Magenta => yansi::Color::Magenta,
+            Color::Cyan => yansi::Color::Cyan,
+            Color::White => yansi::Color::White,
+            Color::BrightBlack => yansi::Color::BrightBlack,
+            Color::BrightRed => yansi::Color::BrightRed,
+            Color::BrightGreen => yansi::Color::BrightGreen,
+            Color::BrightYellow => yansi::Color::BrightYellow,
+            Color::BrightBlue => yansi::Color::BrightBlue,
+            Color::BrightMagenta => yansi::Color::BrightMagenta,
+            Color::BrightCyan => yansi::Color::BrightCyan,
+            Color::BrightWhite => yansi::Color::BrightWhite,
+            Color::Default => yansi::Color::Primary,
+        }
+    }
+
+    /// Convert to a `termcolor::Color` (if the `termcolor` feature is enabled).
+    ///
+    /// `termcolor` has no default color, so `Default` is converted to `White`. Use
+    /// [`Color::try_to_termcolor_color`] to detect it instead. `termcolor` has no bright colors
+    /// either, so they are converted to the corresponding basic color, and
+    /// [`Style::to_termcolor_spec`] sets the "intense" flag instead.
+    #[cfg(feature = "termcolor")]
+    pub fn to_termcolor_color(&self) -> termcolor::Color {
+        self.try_to_termcolor_color()
+            .unwrap_or(termcolor::Color::White)
+    }
+
+    /// Like [`Color::to_termcolor_color`], but returns `None` for `Default` (like in
+    /// [`Style::to_termcolor_spec`], where the color is left unset).
+    #[cfg(feature = "termcolor")]
+    pub fn try_to_termcolor_color(&self) -> Option<termcolor::Color> {
+        Some(match self {
+            Color::RGB(r, g, b) => termcolor::Color::Rgb(*r, *g, *b),
+            Color::Fixed(n) => termcolor::Color::Ansi256(*n),
+            Color::Black => termcolor::Color::Black,
+            Color::Red => termcolor::Color::Red,
+            Color::Green => termcolor::Color::Green,
+            Color::Yellow => termcolor::Color::Yellow,
+            Color::Blue => termcolor::Color::Blue,
+            Color::Magenta => termcolor::Color::Magenta,
+            Color::Cyan => termcolor::Color::Cyan,
+            Color::White => termcolor::Color::White,
+            Color::BrightBlack => termcolor::Color::Black,
+            Color::BrightRed => termcolor::Color::Red,
+            Color::BrightGreen => termcolor::Color::Green,
+            Color::BrightYellow => termcolor::Color::Yellow,
+            Color::BrightBlue => termcolor::Color::Blue,
+            Color::BrightMagenta => termcolor::Color::Magenta,
+            Color::BrightCyan => termcolor::Color::Cyan,
+            Color::BrightWhite => termcolor::Color::White,
+
+            Color::Default => return None,
+        })
+    }
+}
+
+/// The 16 basic ANSI colors, in palette order.
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// The default xterm palette for the 16 basic ANSI colors.
+const ANSI_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// Get the RGB value of an 8-bit color in the (default xterm) 256-color palette.
+fn fixed_to_rgb(n: u8) -> [u8; 3] {
+    match n {
+        0..=15 => ANSI_PALETTE[n as usize],
+        16..=231 => {
+            let level = |i: u8| if i == 0 { 0 } else { 55 + 40 * i };
+            let n = n - 16;
+            [level(n / 36), level(n / 6 % 6), level(n % 6)]
+        }
+        232..=255 => {
+            let gray = 8 + 10 * (n - 232);
+            [gray, gray, gray]
+        }
+    }
+}
+
+/// Get the squared Euclidean distance of two RGB colors.
+fn rgb_distance(a: [u8; 3], b: [u8; 3]) -> i32 {
+    a.into_iter()
+        .zip(b)
+        .map(|(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+        .sum()
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Color::RGB(r, g, b)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color::RGB(r, g, b)
+    }
+}
+
+/// Get the RGB value of a color. Palette colors (`Red`, `Fixed(n)`, ...) are resolved using the
+/// default xterm palette. Colors without an RGB representation are returned as the error.
+impl TryFrom<Color> for [u8; 3] {
+    type Error = Color;
+
+    fn try_from(color: Color) -> Result<Self, Self::Error> {
+        Ok(match color {
+            Color::RGB(r, g, b) => [r, g, b],
+            Color::Fixed(n) => fixed_to_rgb(n),
+            Color::Black => fixed_to_rgb(0),
+            Color::Red => fixed_to_rgb(1),
+            Color::Green => fixed_to_rgb(2),
+            Color::Yellow => fixed_to_rgb(3),
+            Color::Blue => fixed_to_rgb(4),
+            Color::Magenta => fixed_to_rgb(5),
+            Color::Cyan => fixed_to_rgb(6),
+            Color::White => fixed_to_rgb(7),
+            Color::BrightBlack => fixed_to_rgb(8),
+            Color::BrightRed => fixed_to_rgb(9),
+            Color::BrightGreen => fixed_to_rgb(10),
+            Color::BrightYellow => fixed_to_rgb(11),
+            Color::BrightBlue => fixed_to_rgb(12),
+            Color::BrightMagenta => fixed_to_rgb(13),
+            Color::BrightCyan => fixed_to_rgb(14),
+            Color::BrightWhite => fixed_to_rgb(15),
+            Color::Default => return Err(color),
+        })
+    }
 }
 
 /// Font-style attributes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct FontStyle {
     pub bold: bool,
     pub dimmed: bool, // a.k.a. faint
@@ -160,6 +531,8 @@ pub struct FontStyle {
     pub reverse: bool,       // a.k.a. inverse or reverse video
     pub hidden: bool,        // a.k.a. conceal
     pub strikethrough: bool, // a.k.a. crossed-out
+    pub double_underline: bool,
+    pub overline: bool,
 }
 
 impl FontStyle {
@@ -226,7 +599,96 @@ impl FontStyle {
         }
     }
 
+    pub fn double_underline() -> Self {
+        FontStyle {
+            double_underline: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn overline() -> Self {
+        FontStyle {
+            overline: true,
+            ..Default::default()
+        }
+    }
+
+    /// Get the SGR codes of the attributes that are set, in ascending order, e.g. `[1, 4]` for
+    /// bold and underlined text.
+    ///
+    /// ```
+    /// use lscolors::FontStyle;
+    ///
+    /// assert_eq!(vec![1, 4], (FontStyle::bold() | FontStyle::underline()).to_sgr_codes());
+    /// ```
+    pub fn to_sgr_codes(&self) -> Vec<u8> {
+        self.sgr_code_table()
+            .into_iter()
+            .filter_map(|(enabled, code)| enabled.then_some(code))
+            .collect()
+    }
+
+    /// Get the font style with the attributes of the given SGR codes, like `[1, 4]` for bold and
+    /// underlined text. Other codes (including colors and resets) are ignored.
+    pub fn from_sgr_codes(codes: &[u8]) -> FontStyle {
+        let mut font_style = FontStyle::default();
+        for code in codes {
+            let attribute = match code {
+                1 => &mut font_style.bold,
+                2 => &mut font_style.dimmed,
+                3 => &mut font_style.italic,
+                4 => &mut font_style.underline,
+                5 => &mut font_style.slow_blink,
+                6 => &mut font_style.rapid_blink,
+                7 => &mut font_style.reverse,
+                8 => &mut font_style.hidden,
+                9 => &mut font_style.strikethrough,
+                21 => &mut font_style.double_underline,
+                53 => &mut font_style.overline,
+                _ => continue,
+            };
+            *attribute = true;
+        }
+        font_style
+    }
+
+    /// All attributes with their SGR code, in ascending order.
+    fn sgr_code_table(&self) -> [(bool, u8); 11] {
+        [
+            (self.bold, 1),
+            (self.dimmed, 2),
+            (self.italic, 3),
+            (self.underline, 4),
+            (self.slow_blink, 5),
+            (self.rapid_blink, 6),
+            (self.reverse, 7),
+            (self.hidden, 8),
+            (self.strikethrough, 9),
+            (self.double_underline, 21),
+            (self.overline, 53),
+        ]
+    }
+
+    /// Get the font style with all attributes that are set in either `self` or `other`.
+    pub(crate) fn union(&self, other: &FontStyle) -> FontStyle {
+        FontStyle {
+            bold: self.bold || other.bold,
+            dimmed: self.dimmed || other.dimmed,
+            italic: self.italic || other.italic,
+            underline: self.underline || other.underline,
+            slow_blink: self.slow_blink || other.slow_blink,
+            rapid_blink: self.rapid_blink || other.rapid_blink,
+            reverse: self.reverse || other.reverse,
+            hidden: self.hidden || other.hidden,
+            strikethrough: self.strikethrough || other.strikethrough,
+            double_underline: self.double_underline || other.double_underline,
+            overline: self.overline || other.overline,
+        }
+    }
+
     /// Convert to `crossterm::style::Attributes` (if the `crossterm` feature is enabled).
+    ///
+    /// Slow and rapid blinking are kept apart (`SlowBlink` and `RapidBlink`).
     #[cfg(feature = "crossterm")]
     pub fn to_crossterm_attributes(&self) -> crossterm::style::Attributes {
         let mut attributes = crossterm::style::Attributes::default();
@@ -257,12 +719,29 @@ impl FontStyle {
         if self.strikethrough {
             attributes.set(crossterm::style::Attribute::CrossedOut);
         }
+        if self.double_underline {
+            attributes.set(crossterm::style::Attribute::DoubleUnderlined);
+        }
+        if self.overline {
+            attributes.set(crossterm::style::Attribute::OverLined);
+        }
         attributes
     }
 }
 
+/// Get the font style with all attributes that are set in either of the two font styles.
+impl std::ops::BitOr for FontStyle {
+    type Output = FontStyle;
+
+    fn bitor(self, other: FontStyle) -> FontStyle {
+        self.union(&other)
+    }
+}
+
 /// A foreground color, background color and font-style.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Style {
     pub foreground: Option<Color>,
     pub background: Option<Color>,
@@ -272,15 +751,19 @@ pub struct Style {
 
 impl Style {
     /// Parse ANSI escape sequences like `38;2;255;0;100;1;4` (pink, bold, underlined).
+    ///
+    /// Returns `None` for sequences that reset all attributes (`""`, `"0"` and `"00"`), or
+    /// if the sequence can not be parsed. Empty parameters (like in `;1;31`, `1;31;` or `1;;31`)
+    /// are ignored, so `"0;"` is a reset, too.
     pub fn from_ansi_sequence(code: &str) -> Option<Style> {
-        if code.is_empty() || code == "0" || code == "00" {
-            return None;
-        }
-
         let mut parts: VecDeque<u8> = code
             .split(';')
+            .filter(|c| !c.is_empty())
             .map(|c| c.parse::<u8>().ok())
             .collect::<Option<_>>()?;
+        if parts.is_empty() || parts == [0] {
+            return None;
+        }
 
         let mut font_style = FontStyle::default();
         let mut foreground = None;
@@ -299,6 +782,7 @@ impl Style {
                 Some(7) => font_style.reverse = true,
                 Some(8) => font_style.hidden = true,
                 Some(9) => font_style.strikethrough = true,
+                Some(21) => font_style.double_underline = true,
                 Some(22) => {
                     font_style.bold = false;
                     font_style.dimmed = false;
@@ -308,6 +792,7 @@ impl Style {
                 }
                 Some(24) => {
                     font_style.underline = false;
+                    font_style.double_underline = false;
                 }
                 Some(25) => {
                     font_style.slow_blink = false;
@@ -344,7 +829,7 @@ impl Style {
                         break;
                     }
                 },
-                Some(39) => foreground = None,
+                Some(39) => foreground = Some(Color::Default),
                 Some(40) => background = Some(Color::Black),
                 Some(41) => background = Some(Color::Red),
                 Some(42) => background = Some(Color::Green),
@@ -367,7 +852,7 @@ impl Style {
                         break;
                     }
                 },
-                Some(49) => background = None,
+                Some(49) => background = Some(Color::Default),
                 Some(58) => match (parts.pop_front(), parts.pop_front()) {
                     (Some(5), Some(color)) => underline = Some(Color::Fixed(color)),
                     (Some(2), Some(red)) => match (parts.pop_front(), parts.pop_front()) {
@@ -380,7 +865,9 @@ impl Style {
                         break;
                     }
                 },
-                Some(59) => underline = None,
+                Some(53) => font_style.overline = true,
+                Some(55) => font_style.overline = false,
+                Some(59) => underline = Some(Color::Default),
                 Some(90) => foreground = Some(Color::BrightBlack),
                 Some(91) => foreground = Some(Color::BrightRed),
                 Some(92) => foreground = Some(Color::BrightGreen),
@@ -414,12 +901,167 @@ impl Style {
         })
     }
 
+    /// Check whether this style has no colors and no font-style attributes, i.e. whether it is
+    /// equal to `Style::default()`. Text with such a style can be written without any escape
+    /// sequences.
+    pub fn is_default(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// Layer `other` on top of this style: the foreground, background and underline colors of
+    /// `other` take precedence if they are set, otherwise the colors of `self` are kept. The font
+    /// style attributes of both are combined, e.g. bold and italic results in bold italic text.
+    pub fn combine(&self, other: &Style) -> Style {
+        Style {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            font_style: self.font_style.union(&other.font_style),
+            underline: other.underline.or(self.underline),
+        }
+    }
+
+    /// Iterate over the foreground, background and underline colors that are set.
+    pub fn colors(&self) -> impl Iterator<Item = Color> {
+        [self.foreground, self.background, self.underline]
+            .into_iter()
+            .flatten()
+    }
+
+    /// Check whether any of the colors is a 24-bit color (`RGB`).
+    pub fn uses_truecolor(&self) -> bool {
+        self.colors().any(|c| c.level() == ColorLevel::TrueColor)
+    }
+
+    /// Check whether any of the colors is an 8-bit color (`Fixed`).
+    pub fn uses_256(&self) -> bool {
+        self.colors().any(|c| c.level() == ColorLevel::Ansi256)
+    }
+
+    /// Approximate all colors by the nearest of the 16 basic ANSI colors, see
+    /// [`Color::approximate_ansi16`].
+    pub fn downgrade_to_ansi16(&self) -> Style {
+        Style {
+            foreground: self.foreground.map(|c| c.approximate_ansi16()),
+            background: self.background.map(|c| c.approximate_ansi16()),
+            underline: self.underline.map(|c| c.approximate_ansi16()),
+            ..*self
+        }
+    }
+
+    /// Convert all `RGB` colors to the nearest color of the 256-color palette, see
+    /// [`Color::to_fixed256`].
+    pub fn downgrade_to_256(&self) -> Style {
+        Style {
+            foreground: self.foreground.map(|c| c.to_fixed256()),
+            background: self.background.map(|c| c.to_fixed256()),
+            underline: self.underline.map(|c| c.to_fixed256()),
+            ..*self
+        }
+    }
+
+    /// Get the SGR codes of the font-style attributes (without any colors), e.g. `[1, 4]` for
+    /// bold and underlined text. See [`FontStyle::to_sgr_codes`].
+    pub fn font_style_codes(&self) -> Vec<u8> {
+        self.font_style.to_sgr_codes()
+    }
+
+    /// Get the SGR parameters for the underline color, e.g. `58;5;202` or `58;2;255;0;100`. The
+    /// basic colors are given by their palette index (`58;5;1` for `Red`), since there are no
+    /// short forms for underline colors. The default color is `59`.
+    ///
+    /// This can be used with backends that don't support underline colors (like `ansi_term`):
+    ///
+    /// ```
+    /// use lscolors::Style;
+    ///
+    /// let style = Style::from_ansi_sequence("4;58;5;202").unwrap();
+    /// let underline = style.underline_ansi_sequence().unwrap();
+    /// let painted = format!("\x1b[{}m{}\x1b[59m", underline, "text");
+    /// assert_eq!("\x1b[58;5;202mtext\x1b[59m", painted);
+    /// ```
+    pub fn underline_ansi_sequence(&self) -> Option<String> {
+        let mut sequence = String::new();
+        self.underline?
+            .write_underline_sequence(&mut sequence)
+            .ok()?;
+        Some(sequence)
+    }
+
+    /// Encode the style as SGR parameters, like `01;34` or `38;5;202;48;2;0;0;0`. Font-style
+    /// attributes come first (padded to two digits, like in the output of `dircolors`), followed
+    /// by the foreground, background and underline colors.
+    ///
+    /// ```
+    /// use lscolors::Style;
+    ///
+    /// let style = Style::from_ansi_sequence("34;1").unwrap();
+    /// assert_eq!("01;34", style.to_ansi_sequence());
+    /// ```
+    pub fn to_ansi_sequence(&self) -> String {
+        let mut sequence = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_ansi_sequence(&mut sequence);
+        sequence
+    }
+
+    /// Write the SGR parameters of [`Style::to_ansi_sequence`] to `out`, without allocating.
+    pub(crate) fn write_ansi_sequence<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        let mut separator = "";
+        let font_codes = self.font_style.sgr_code_table();
+        for (_, code) in font_codes.into_iter().filter(|(enabled, _)| *enabled) {
+            write!(out, "{}{:02}", separator, code)?;
+            separator = ";";
+        }
+        for (color, base) in [(self.foreground, 30), (self.background, 40)] {
+            if let Some(color) = color {
+                out.write_str(separator)?;
+                color.write_ansi_sequence(out, base)?;
+                separator = ";";
+            }
+        }
+        if let Some(color) = self.underline {
+            out.write_str(separator)?;
+            color.write_underline_sequence(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Paint `content` in this style, without the need for a terminal styling crate. The content
+    /// is surrounded by the SGR codes of the style (see [`Style::to_ansi_sequence`]) and a reset
+    /// (`\x1b[0m`), unless the style is the default style.
+    ///
+    /// ```
+    /// use lscolors::Style;
+    ///
+    /// let style = Style::from_ansi_sequence("01;34").unwrap();
+    /// assert_eq!("\x1b[01;34mwow\x1b[0m", style.paint("wow").to_string());
+    ///
+    /// assert_eq!("wow", Style::default().paint("wow").to_string());
+    /// ```
+    pub fn paint<D: fmt::Display>(&self, content: D) -> impl fmt::Display {
+        Painted {
+            style: *self,
+            content,
+        }
+    }
+
     /// Convert to a `ansi_term::Style` (if the `ansi_term` feature is enabled).
+    ///
+    /// Since `ansi_term` has no default color, `Color::Default` is left unset, which has the same
+    /// effect unless the text is nested in other styled text. Double underlines and overlines are
+    /// not supported by `ansi_term` and are dropped.
+    ///
+    /// `ansi_term` only has a single blink attribute (SGR `5`), so rapid blinking is converted to
+    /// slow blinking, and [`Style::from_ansi_term_style`] can not restore it.
     #[cfg(feature = "ansi_term")]
     pub fn to_ansi_term_style(&self) -> ansi_term::Style {
+        let convert =
+            |color: &Option<Color>| color.as_ref().and_then(Color::try_to_ansi_term_color);
+
         ansi_term::Style {
-            foreground: self.foreground.as_ref().map(Color::to_ansi_term_color),
-            background: self.background.as_ref().map(Color::to_ansi_term_color),
+            foreground: convert(&self.foreground),
+            background: convert(&self.background),
             is_bold: self.font_style.bold,
             is_dimmed: self.font_style.dimmed,
             is_italic: self.font_style.italic,
@@ -431,8 +1073,36 @@ impl Style {
         }
     }
 
+    /// Convert from a `ansi_term::Style` (if the `ansi_term` feature is enabled).
+    ///
+    /// `ansi_term` does not distinguish between slow and rapid blinking, so `is_blink` is
+    /// converted to `slow_blink`.
+    #[cfg(feature = "ansi_term")]
+    pub fn from_ansi_term_style(style: &ansi_term::Style) -> Style {
+        Style {
+            foreground: style.foreground.as_ref().map(Color::from_ansi_term_color),
+            background: style.background.as_ref().map(Color::from_ansi_term_color),
+            font_style: FontStyle {
+                bold: style.is_bold,
+                dimmed: style.is_dimmed,
+                italic: style.is_italic,
+                underline: style.is_underline,
+                slow_blink: style.is_blink,
+                reverse: style.is_reverse,
+                hidden: style.is_hidden,
+                strikethrough: style.is_strikethrough,
+                ..Default::default()
+            },
+            underline: None,
+        }
+    }
+
     /// Convert to a `nu_ansi_term::Style` (if the `nu-ansi-term` or `gnu_legacy` feature is enabled).
     ///
+    /// Double underlines and overlines are not supported by `nu_ansi_term` and are dropped.
+    /// `nu_ansi_term` only has a single blink attribute (SGR `5`), so rapid blinking is converted
+    /// to slow blinking, and [`Style::from_nu_ansi_term_style`] can not restore it.
+    ///
     /// ## Example for nu-ansi-term feature
     /// ```
     /// # #[cfg(all(feature = "nu-ansi-term", not(feature = "gnu_legacy")))]
@@ -495,6 +1165,37 @@ impl Style {
         }
     }
 
+    /// Convert from a `nu_ansi_term::Style` (if the `nu-ansi-term` or `gnu_legacy` feature is
+    /// enabled).
+    ///
+    /// `nu_ansi_term` does not distinguish between slow and rapid blinking, so `is_blink` is
+    /// converted to `slow_blink`.
+    #[cfg(any(feature = "nu-ansi-term", feature = "gnu_legacy"))]
+    pub fn from_nu_ansi_term_style(style: &nu_ansi_term::Style) -> Style {
+        Style {
+            foreground: style
+                .foreground
+                .as_ref()
+                .map(Color::from_nu_ansi_term_color),
+            background: style
+                .background
+                .as_ref()
+                .map(Color::from_nu_ansi_term_color),
+            font_style: FontStyle {
+                bold: style.is_bold,
+                dimmed: style.is_dimmed,
+                italic: style.is_italic,
+                underline: style.is_underline,
+                slow_blink: style.is_blink,
+                reverse: style.is_reverse,
+                hidden: style.is_hidden,
+                strikethrough: style.is_strikethrough,
+                ..Default::default()
+            },
+            underline: None,
+        }
+    }
+
     /// Convert to a `crossterm::style::ContentStyle` (if the `crossterm` feature is enabled).
     #[cfg(feature = "crossterm")]
     pub fn to_crossterm_style(&self) -> crossterm::style::ContentStyle {
@@ -507,6 +1208,8 @@ impl Style {
     }
 
     /// Convert to a `owo_colors::Style` (if the `owo-colors` feature is enabled).
+    ///
+    /// Double underlines and overlines are not supported by `owo_colors` and are dropped.
     #[cfg(feature = "owo-colors")]
     pub fn to_owo_colors_style(&self) -> owo_colors::Style {
         let mut style = owo_colors::Style::new();
@@ -547,6 +1250,98 @@ impl Style {
         // TODO: Implement colored underline. owo-colors does not support it at the time of writing.
         style
     }
+
+    /// Convert to a `termcolor::ColorSpec` (if the `termcolor` feature is enabled).
+    ///
+    /// Blinking, reversed and hidden text, double underlines, overlines and underline colors are
+    /// not supported by `termcolor` and are dropped. Default colors are left unset.
+    ///
+    /// Bright colors are converted to the basic colors with the "intense" flag set. `termcolor`
+    /// only has one "intense" flag for both the foreground and the background color, so if only
+    /// one of them is bright, a basic color on the other one is shown as bright, too.
+    #[cfg(feature = "termcolor")]
+    pub fn to_termcolor_spec(&self) -> termcolor::ColorSpec {
+        let convert =
+            |color: &Option<Color>| color.as_ref().and_then(Color::try_to_termcolor_color);
+        let is_bright = |color: &Option<Color>| {
+            matches!(color.as_ref().and_then(Color::ansi_index), Some(8..=15))
+        };
+
+        let mut spec = termcolor::ColorSpec::new();
+        spec.set_fg(convert(&self.foreground))
+            .set_bg(convert(&self.background))
+            .set_bold(self.font_style.bold)
+            .set_dimmed(self.font_style.dimmed)
+            .set_italic(self.font_style.italic)
+            .set_underline(self.font_style.underline)
+            .set_strikethrough(self.font_style.strikethrough)
+            .set_intense(is_bright(&self.foreground) || is_bright(&self.background));
+        spec
+    }
+
+    /// Convert to a `yansi::Style` (if the `yansi` feature is enabled).
+    ///
+    /// Double underlines, overlines and underline colors are not supported by `yansi` and are
+    /// dropped.
+    #[cfg(feature = "yansi")]
+    pub fn to_yansi_style(&self) -> yansi::Style {
+        let mut style = yansi::Style::new();
+        if let Some(ref c) = self.foreground {
+            style = style.fg(c.to_yansi_color())
+        }
+        if let Some(ref b) = self.background {
+            style = style.bg(b.to_yansi_color())
+        }
+        if self.font_style.bold {
+            style = style.bold()
+        }
+        if self.font_style.dimmed {
+            style = style.dim()
+        }
+        if self.font_style.italic {
+            style = style.italic()
+        }
+        if self.font_style.underline {
+            style = style.underline()
+        }
+        if self.font_style.slow_blink {
+            style = style.blink()
+        }
+        if self.font_style.rapid_blink {
+            style = style.rapid_blink()
+        }
+        if self.font_style.reverse {
+            style = style.invert()
+        }
+        if self.font_style.hidden {
+            style = style.conceal()
+        }
+        if self.font_style.strikethrough {
+            style = style.strike()
+        }
+        style
+    }
+}
+
+/// Text painted in a [Style], see [`Style::paint`].
+struct Painted<D> {
+    style: Style,
+    content: D,
+}
+
+impl<D: fmt::Display> fmt::Display for Painted<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.style.is_default() {
+            self.content.fmt(f)
+        } else {
+            write!(
+                f,
+                "\x1b[{}m{}\x1b[0m",
+                self.style.to_ansi_sequence(),
+                self.content
+            )
+        }
+    }
 }
 
 #[cfg(test)]
@@ -597,10 +1392,31 @@ mod tests {
     #[test]
     fn parse_reject() {
         assert_eq!(None, Style::from_ansi_sequence("a"));
-        assert_eq!(None, Style::from_ansi_sequence("1;"));
         assert_eq!(None, Style::from_ansi_sequence("33; 42"));
     }
 
+    #[test]
+    fn parse_empty_parameters() {
+        let bold_red = Style {
+            foreground: Some(Color::Red),
+            font_style: FontStyle::bold(),
+            ..Default::default()
+        };
+
+        assert_eq!(Some(bold_red), Style::from_ansi_sequence(";1;31"));
+        assert_eq!(Some(bold_red), Style::from_ansi_sequence("1;31;"));
+        assert_eq!(Some(bold_red), Style::from_ansi_sequence("1;;31"));
+        assert_eq!(Some(bold_red), Style::from_ansi_sequence(";;1;31;;"));
+        assert_eq!(None, Style::from_ansi_sequence(";"));
+        assert_eq!(None, Style::from_ansi_sequence(";;"));
+
+        // A single reset is parsed the same way, with or without separators
+        assert_eq!(None, Style::from_ansi_sequence("0"));
+        assert_eq!(None, Style::from_ansi_sequence("0;"));
+        assert_eq!(None, Style::from_ansi_sequence("00;"));
+        assert_eq!(None, Style::from_ansi_sequence(";00"));
+    }
+
     #[test]
     fn parse_font_style() {
         assert_style("00;31", Some(Color::Red), None, None, FontStyle::default());
@@ -639,6 +1455,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_default_colors() {
+        assert_style("39", Some(Color::Default), None, None, FontStyle::default());
+        assert_style("49", None, Some(Color::Default), None, FontStyle::default());
+        assert_style("59", None, None, Some(Color::Default), FontStyle::default());
+        assert_style(
+            "31;41;39;49",
+            Some(Color::Default),
+            Some(Color::Default),
+            None,
+            FontStyle::default(),
+        );
+        assert_style(
+            "01;39;44",
+            Some(Color::Default),
+            Some(Color::Blue),
+            None,
+            FontStyle::bold(),
+        );
+    }
+
+    #[test]
+    fn parse_overline_and_double_underline() {
+        assert_style("53", None, None, None, FontStyle::overline());
+        assert_style("21", None, None, None, FontStyle::double_underline());
+        assert_style("53;31", Some(Color::Red), None, None, FontStyle::overline());
+        assert_style("53;55", None, None, None, FontStyle::default());
+        assert_style("21;24", None, None, None, FontStyle::default());
+        assert_style("04;21;24", None, None, None, FontStyle::default());
+        assert_style("53;00", None, None, None, FontStyle::default());
+    }
+
     #[test]
     fn parse_font_style_backwards() {
         assert_style("34;03", Some(Color::Blue), None, None, FontStyle::italic());
@@ -731,6 +1579,277 @@ mod tests {
         );
     }
 
+    #[test]
+    fn font_style_sgr_codes() {
+        assert_eq!(
+            vec![1, 4],
+            (FontStyle::bold() | FontStyle::underline()).to_sgr_codes()
+        );
+        assert_eq!(Vec::<u8>::new(), FontStyle::default().to_sgr_codes());
+
+        // Ascending order, independent of the order of the codes
+        let font_style = FontStyle::from_sgr_codes(&[53, 9, 3, 1, 21]);
+        assert_eq!(vec![1, 3, 9, 21, 53], font_style.to_sgr_codes());
+
+        // Other codes are ignored
+        assert_eq!(
+            FontStyle::bold(),
+            FontStyle::from_sgr_codes(&[0, 1, 22, 31])
+        );
+
+        let font_styles = [
+            FontStyle::default(),
+            FontStyle::bold() | FontStyle::italic(),
+            FontStyle::dimmed() | FontStyle::slow_blink() | FontStyle::rapid_blink(),
+            FontStyle::reverse() | FontStyle::hidden() | FontStyle::strikethrough(),
+            FontStyle::underline() | FontStyle::double_underline() | FontStyle::overline(),
+        ];
+        for font_style in font_styles {
+            assert_eq!(
+                font_style,
+                FontStyle::from_sgr_codes(&font_style.to_sgr_codes())
+            );
+        }
+    }
+
+    #[test]
+    fn font_style_codes() {
+        let codes = |font_style| {
+            Style {
+                font_style,
+                ..Default::default()
+            }
+            .font_style_codes()
+        };
+
+        assert_eq!(Vec::<u8>::new(), codes(FontStyle::default()));
+        assert_eq!(vec![1], codes(FontStyle::bold()));
+        assert_eq!(vec![2], codes(FontStyle::dimmed()));
+        assert_eq!(vec![3], codes(FontStyle::italic()));
+        assert_eq!(vec![4], codes(FontStyle::underline()));
+        assert_eq!(vec![5], codes(FontStyle::slow_blink()));
+        assert_eq!(vec![6], codes(FontStyle::rapid_blink()));
+        assert_eq!(vec![7], codes(FontStyle::reverse()));
+        assert_eq!(vec![8], codes(FontStyle::hidden()));
+        assert_eq!(vec![9], codes(FontStyle::strikethrough()));
+        assert_eq!(vec![21], codes(FontStyle::double_underline()));
+        assert_eq!(vec![53], codes(FontStyle::overline()));
+
+        assert_eq!(
+            vec![1, 4],
+            codes(FontStyle {
+                bold: true,
+                underline: true,
+                ..Default::default()
+            })
+        );
+
+        // Colors are not included
+        let style = Style::from_ansi_sequence("38;5;202;1;41;3;58;2;1;2;3").unwrap();
+        assert_eq!(vec![1, 3], style.font_style_codes());
+    }
+
+    #[test]
+    fn underline_ansi_sequence() {
+        let sequence = |code| {
+            Style::from_ansi_sequence(code)
+                .unwrap()
+                .underline_ansi_sequence()
+        };
+
+        assert_eq!(Some("58;5;202".into()), sequence("4;58;5;202"));
+        assert_eq!(Some("58;5;1".into()), sequence("58;5;1"));
+        assert_eq!(Some("58;2;255;0;100".into()), sequence("4;58;2;255;0;100"));
+        assert_eq!(None, sequence("4;31"));
+
+        let style = Style {
+            underline: Some(Color::BrightCyan),
+            ..Style::default()
+        };
+        assert_eq!(Some("58;5;14".into()), style.underline_ansi_sequence());
+
+        // Roundtrip
+        let style = Style::from_ansi_sequence("58;2;1;2;3").unwrap();
+        let sequence = style.underline_ansi_sequence().unwrap();
+        assert_eq!(Some(style), Style::from_ansi_sequence(&sequence));
+    }
+
+    #[test]
+    fn to_ansi_sequence() {
+        let roundtrip = |code| Style::from_ansi_sequence(code).unwrap().to_ansi_sequence();
+
+        assert_eq!("01;34", roundtrip("01;34"));
+        assert_eq!("01;34", roundtrip("34;1"));
+        assert_eq!("37;41", roundtrip("37;41"));
+        assert_eq!("01;05;37;41", roundtrip("01;05;37;41"));
+        assert_eq!("91;107", roundtrip("91;107"));
+        assert_eq!("38;5;202;48;2;0;0;0", roundtrip("38;5;202;48;2;0;0;0"));
+        assert_eq!("04;31;58;5;1", roundtrip("4;31;58;5;1"));
+        assert_eq!("39;49;59", roundtrip("39;49;59"));
+        assert_eq!("", Style::default().to_ansi_sequence());
+
+        for code in [
+            "01;02;03;04;05;06;07;08;09",
+            "30;40",
+            "97;100",
+            "38;2;1;2;3",
+        ] {
+            let style = Style::from_ansi_sequence(code).unwrap();
+            assert_eq!(
+                Some(style),
+                Style::from_ansi_sequence(&style.to_ansi_sequence())
+            );
+        }
+    }
+
+    #[test]
+    fn paint() {
+        let style = Style::from_ansi_sequence("38;5;202;4;48;2;0;0;0").unwrap();
+        assert_eq!(
+            "\x1b[04;38;5;202;48;2;0;0;0mfile.rs\x1b[0m",
+            style.paint("file.rs").to_string()
+        );
+        assert_eq!(
+            "\x1b[31m42\x1b[0m",
+            Style::from_ansi_sequence("31")
+                .unwrap()
+                .paint(42)
+                .to_string()
+        );
+        assert_eq!("42", Style::default().paint(42).to_string());
+    }
+
+    #[test]
+    fn is_default() {
+        assert!(Style::default().is_default());
+        assert!(!Style::from_ansi_sequence("01").unwrap().is_default());
+        assert!(!Style::from_ansi_sequence("31").unwrap().is_default());
+        assert!(!Style::from_ansi_sequence("58;5;1").unwrap().is_default());
+        // Only reset codes
+        assert!(Style::from_ansi_sequence("0;0").unwrap().is_default());
+    }
+
+    #[test]
+    fn combine() {
+        let base = Style::from_ansi_sequence("31;44;3").unwrap();
+        let other = Style::from_ansi_sequence("01;32").unwrap();
+
+        let combined = base.combine(&other);
+        assert_eq!(Some(Color::Green), combined.foreground);
+        assert_eq!(Some(Color::Blue), combined.background);
+        assert_eq!(None, combined.underline);
+        assert_eq!(
+            FontStyle {
+                bold: true,
+                italic: true,
+                ..FontStyle::default()
+            },
+            combined.font_style
+        );
+
+        let combined = other.combine(&base);
+        assert_eq!(Some(Color::Red), combined.foreground);
+        assert_eq!(Some(Color::Blue), combined.background);
+
+        let underlined = Style::from_ansi_sequence("4;58;5;202").unwrap();
+        let combined = base.combine(&underlined);
+        assert_eq!(Some(Color::Red), combined.foreground);
+        assert_eq!(Some(Color::Fixed(202)), combined.underline);
+        assert!(combined.font_style.underline && combined.font_style.italic);
+
+        assert_eq!(base, base.combine(&Style::default()));
+        assert_eq!(base, Style::default().combine(&base));
+    }
+
+    #[test]
+    fn color_levels() {
+        let style = Style::from_ansi_sequence("31;48;5;202").unwrap();
+        assert_eq!(
+            vec![Color::Red, Color::Fixed(202)],
+            style.colors().collect::<Vec<_>>()
+        );
+        assert!(style.uses_256());
+        assert!(!style.uses_truecolor());
+
+        let style = Style::from_ansi_sequence("1;58;2;1;2;3").unwrap();
+        assert_eq!(ColorLevel::TrueColor, style.underline.unwrap().level());
+        assert!(!style.uses_256());
+        assert!(style.uses_truecolor());
+
+        let style = Style::from_ansi_sequence("01;91").unwrap();
+        assert_eq!(ColorLevel::Ansi16, style.foreground.unwrap().level());
+        assert!(!style.uses_256());
+        assert!(!style.uses_truecolor());
+    }
+
+    #[test]
+    fn rgb_conversions() {
+        assert_eq!(Color::RGB(1, 2, 3), Color::from([1, 2, 3]));
+        assert_eq!(Color::RGB(1, 2, 3), Color::from((1, 2, 3)));
+        assert_eq!(Ok([1, 2, 3]), <[u8; 3]>::try_from(Color::RGB(1, 2, 3)));
+
+        // Palette colors are resolved
+        assert_eq!(Ok([205, 0, 0]), <[u8; 3]>::try_from(Color::Red));
+        assert_eq!(Ok([255, 255, 255]), <[u8; 3]>::try_from(Color::BrightWhite));
+        assert_eq!(Ok([205, 0, 0]), <[u8; 3]>::try_from(Color::Fixed(1)));
+        assert_eq!(Ok([0, 0, 0]), <[u8; 3]>::try_from(Color::Fixed(16)));
+        assert_eq!(Ok([255, 135, 0]), <[u8; 3]>::try_from(Color::Fixed(208)));
+        assert_eq!(Ok([255, 255, 255]), <[u8; 3]>::try_from(Color::Fixed(231)));
+        assert_eq!(Ok([8, 8, 8]), <[u8; 3]>::try_from(Color::Fixed(232)));
+        assert_eq!(Ok([238, 238, 238]), <[u8; 3]>::try_from(Color::Fixed(255)));
+    }
+
+    #[test]
+    fn approximate_ansi16() {
+        assert_eq!(Color::BrightRed, Color::Fixed(196).approximate_ansi16());
+        assert_eq!(Color::Blue, Color::Fixed(21).approximate_ansi16());
+        assert_eq!(Color::BrightBlack, Color::Fixed(244).approximate_ansi16());
+        assert_eq!(Color::Yellow, Color::Fixed(3).approximate_ansi16());
+        assert_eq!(Color::Black, Color::RGB(10, 10, 10).approximate_ansi16());
+        assert_eq!(
+            Color::BrightWhite,
+            Color::RGB(250, 250, 250).approximate_ansi16()
+        );
+        assert_eq!(Color::Green, Color::RGB(20, 190, 30).approximate_ansi16());
+
+        // Basic and default colors are unchanged
+        assert_eq!(Color::BrightBlue, Color::BrightBlue.approximate_ansi16());
+        assert_eq!(Color::Default, Color::Default.approximate_ansi16());
+
+        let style = Style::from_ansi_sequence("01;38;5;196;48;2;0;0;0;58;5;21").unwrap();
+        let downgraded = style.downgrade_to_ansi16();
+        assert_eq!(Some(Color::BrightRed), downgraded.foreground);
+        assert_eq!(Some(Color::Black), downgraded.background);
+        assert_eq!(Some(Color::Blue), downgraded.underline);
+        assert_eq!(style.font_style, downgraded.font_style);
+        assert!(downgraded.colors().all(|c| c.level() == ColorLevel::Ansi16));
+    }
+
+    #[test]
+    fn to_fixed256() {
+        assert_eq!(Color::Fixed(16), Color::RGB(0, 0, 0).to_fixed256());
+        assert_eq!(Color::Fixed(231), Color::RGB(255, 255, 255).to_fixed256());
+        assert_eq!(Color::Fixed(196), Color::RGB(255, 0, 0).to_fixed256());
+        assert_eq!(Color::Fixed(208), Color::RGB(255, 140, 0).to_fixed256());
+
+        // Grays use the grayscale ramp instead of the color cube
+        assert_eq!(Color::Fixed(244), Color::RGB(128, 128, 128).to_fixed256());
+        assert_eq!(Color::Fixed(232), Color::RGB(8, 8, 8).to_fixed256());
+        assert_eq!(Color::Fixed(255), Color::RGB(240, 240, 240).to_fixed256());
+
+        // Everything else is unchanged
+        assert_eq!(Color::Red, Color::Red.to_fixed256());
+        assert_eq!(Color::Fixed(42), Color::Fixed(42).to_fixed256());
+        assert_eq!(Color::Default, Color::Default.to_fixed256());
+
+        let style = Style::from_ansi_sequence("04;38;2;255;0;0;44;58;2;128;128;128").unwrap();
+        let downgraded = style.downgrade_to_256();
+        assert_eq!(Some(Color::Fixed(196)), downgraded.foreground);
+        assert_eq!(Some(Color::Blue), downgraded.background);
+        assert_eq!(Some(Color::Fixed(244)), downgraded.underline);
+        assert_eq!(style.font_style, downgraded.font_style);
+    }
+
     #[cfg(all(feature = "nu-ansi-term", not(feature = "gnu_legacy")))]
     #[test]
     fn coloring_nu_ansi_term() {
@@ -836,6 +1955,91 @@ mod tests {
         };
         let ansi = style.to_ansi_term_style();
         assert_eq!("\x1b[1;34mwow\x1b[0m", ansi.paint("wow").to_string());
+
+        // Not supported by ansi_term
+        let style = Style::from_ansi_sequence("21;53").unwrap();
+        assert_eq!(ansi_term::Style::default(), style.to_ansi_term_style());
+
+        // ansi_term has no default color
+        assert_eq!(None, Color::Default.try_to_ansi_term_color());
+        assert_eq!(ansi_term::Color::White, Color::Default.to_ansi_term_color());
+        assert_eq!(
+            Some(ansi_term::Color::Blue),
+            Color::Blue.try_to_ansi_term_color()
+        );
+        let style = Style::from_ansi_sequence("39;49").unwrap();
+        assert_eq!(ansi_term::Style::default(), style.to_ansi_term_style());
+    }
+
+    #[cfg(feature = "ansi_term")]
+    #[test]
+    fn from_ansi_term_style() {
+        for code in [
+            "01;34",
+            "02;03;04;05;07;08;09",
+            "31;42",
+            "38;5;202;48;2;1;2;3",
+            "35;47",
+        ] {
+            let style = Style::from_ansi_sequence(code).unwrap();
+            assert_eq!(
+                style,
+                Style::from_ansi_term_style(&style.to_ansi_term_style())
+            );
+        }
+
+        let style = ansi_term::Style::new()
+            .blink()
+            .fg(ansi_term::Color::Fixed(9));
+        let converted = Style::from_ansi_term_style(&style);
+        assert!(converted.font_style.slow_blink);
+        assert_eq!(Some(Color::Fixed(9)), converted.foreground);
+
+        // ansi_term can not represent rapid blinking
+        let rapid = Style::from_ansi_sequence("06").unwrap();
+        let ansi = rapid.to_ansi_term_style();
+        assert_eq!("\x1b[5mwow\x1b[0m", ansi.paint("wow").to_string());
+        assert_eq!(
+            Style::from_ansi_sequence("05"),
+            Some(Style::from_ansi_term_style(&ansi))
+        );
+    }
+
+    #[cfg(any(feature = "nu-ansi-term", feature = "gnu_legacy"))]
+    #[test]
+    fn from_nu_ansi_term_style() {
+        for code in [
+            "01;34",
+            "02;03;04;05;07;08;09",
+            "31;42",
+            "38;5;202;48;2;1;2;3",
+            "35;47",
+            "39;49",
+        ] {
+            let style = Style::from_ansi_sequence(code).unwrap();
+            assert_eq!(
+                style,
+                Style::from_nu_ansi_term_style(&style.to_nu_ansi_term_style())
+            );
+        }
+
+        let style = nu_ansi_term::Style::new()
+            .blink()
+            .fg(nu_ansi_term::Color::Purple)
+            .on(nu_ansi_term::Color::LightRed);
+        let converted = Style::from_nu_ansi_term_style(&style);
+        assert!(converted.font_style.slow_blink);
+        assert_eq!(Some(Color::Magenta), converted.foreground);
+        assert_eq!(Some(Color::BrightRed), converted.background);
+
+        // nu_ansi_term can not represent rapid blinking
+        let rapid = Style::from_ansi_sequence("06").unwrap();
+        let nu_ansi = rapid.to_nu_ansi_term_style();
+        assert!(nu_ansi.is_blink);
+        assert_eq!(
+            Style::from_ansi_sequence("05"),
+            Some(Style::from_nu_ansi_term_style(&nu_ansi))
+        );
     }
 
     #[cfg(feature = "crossterm")]
@@ -854,6 +2058,38 @@ mod tests {
             "\x1b[38;5;4m\x1b[1mwow\x1b[0m",
             cross.apply("wow").to_string()
         );
+
+        let attributes = Style::from_ansi_sequence("21;53")
+            .unwrap()
+            .font_style
+            .to_crossterm_attributes();
+        assert!(attributes.has(crossterm::style::Attribute::DoubleUnderlined));
+        assert!(attributes.has(crossterm::style::Attribute::OverLined));
+        assert!(!attributes.has(crossterm::style::Attribute::Underlined));
+    }
+
+    #[cfg(feature = "crossterm")]
+    #[test]
+    fn crossterm_blink() {
+        use crossterm::style::Attribute;
+
+        let slow = Style::from_ansi_sequence("05").unwrap();
+        let attributes = slow.font_style.to_crossterm_attributes();
+        assert!(attributes.has(Attribute::SlowBlink));
+        assert!(!attributes.has(Attribute::RapidBlink));
+        assert_eq!(
+            "\x1b[5mwow\x1b[0m",
+            slow.to_crossterm_style().apply("wow").to_string()
+        );
+
+        let rapid = Style::from_ansi_sequence("06").unwrap();
+        let attributes = rapid.font_style.to_crossterm_attributes();
+        assert!(!attributes.has(Attribute::SlowBlink));
+        assert!(attributes.has(Attribute::RapidBlink));
+        assert_eq!(
+            "\x1b[6mwow\x1b[0m",
+            rapid.to_crossterm_style().apply("wow").to_string()
+        );
     }
 
     #[cfg(feature = "owo-colors")]
@@ -871,4 +2107,56 @@ mod tests {
         let owo = style.to_owo_colors_style();
         assert_eq!("\x1b[34;1mwow\x1b[0m", "wow".style(owo).to_string());
     }
+
+    #[cfg(feature = "termcolor")]
+    #[test]
+    fn coloring_termcolor() {
+        let style = Style::from_ansi_sequence("01;03;04;09;05;07;38;5;202;101").unwrap();
+        let spec = style.to_termcolor_spec();
+        assert_eq!(Some(&termcolor::Color::Ansi256(202)), spec.fg());
+        assert_eq!(Some(&termcolor::Color::Red), spec.bg());
+        assert!(spec.bold());
+        assert!(!spec.dimmed());
+        assert!(spec.italic());
+        assert!(spec.underline());
+        assert!(spec.strikethrough());
+        assert!(spec.intense());
+
+        let spec = Style::from_ansi_sequence("92").unwrap().to_termcolor_spec();
+        assert_eq!(Some(&termcolor::Color::Green), spec.fg());
+        assert!(spec.intense());
+        let mut buffer = termcolor::Buffer::ansi();
+        termcolor::WriteColor::set_color(&mut buffer, &spec).unwrap();
+        assert_eq!(b"\x1b[0m\x1b[38;5;10m", buffer.as_slice());
+
+        let style = Style::from_ansi_sequence("02;34;49").unwrap();
+        let spec = style.to_termcolor_spec();
+        assert_eq!(Some(&termcolor::Color::Blue), spec.fg());
+        assert_eq!(None, spec.bg());
+        assert!(spec.dimmed());
+        assert!(!spec.bold());
+        assert!(!spec.intense());
+        assert_eq!(None, Color::Default.try_to_termcolor_color());
+        assert_eq!(termcolor::Color::White, Color::Default.to_termcolor_color());
+
+        let mut buffer = termcolor::Buffer::ansi();
+        termcolor::WriteColor::set_color(&mut buffer, &spec).unwrap();
+        assert_eq!(b"\x1b[0m\x1b[2m\x1b[34m", buffer.as_slice());
+    }
+
+    #[cfg(feature = "yansi")]
+    #[test]
+    fn coloring_yansi() {
+        use yansi::Paint;
+        let style = Style {
+            font_style: FontStyle {
+                bold: true,
+                ..Default::default()
+            },
+            foreground: Some(Color::Blue),
+            ..Default::default()
+        };
+        let yansi = style.to_yansi_style();
+        assert_eq!("\x1b[1;34mwow\x1b[0m", "wow".paint(yansi).to_string());
+    }
 }
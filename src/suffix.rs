@@ -21,7 +21,8 @@
 //! export LS_COLORS="*.tar.gz=01;33:*.gz=01;31:"
 //! ```
 //!
-//! will color them both the same.
+//! will color them both the same. Alternatively, the longest matching suffix can win instead
+//! (see [SuffixMatch]).
 //!
 //! Matching is ASCII case insensitive, unless two different capitalizations with different styles
 //! are given for the same suffix:
@@ -29,13 +30,53 @@
 //! ```text
 //! export LS_COLORS="*README=01:*readme=00:"
 //! ```
+//!
+//! Case insensitive matching can also be disabled for all suffixes, or extended to non-ASCII
+//! letters (so `*.DatENsätze` matches `.datensätze`).
+//!
+//! As an extension to GNU ls, a suffix ending in `.*` matches the rest of the suffix followed by
+//! one or more numeric version components. This is useful for versioned shared libraries:
+//!
+//! ```text
+//! export LS_COLORS="*.so=01;32:*.so.*=32:"
+//! ```
+//!
+//! will color `libfoo.so` bold green, and `libfoo.so.1` and `libfoo.so.1.2.3` green. Among all
+//! matching suffixes (e.g. `*.so.*` and `*.1` for `libfoo.so.1`), the later one wins as usual.
 
 use aho_corasick::{AhoCorasick, Anchored, Input, MatchKind, StartKind};
 
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
 use crate::style::Style;
 
+/// Suffixes up to this length (in bytes) are matched without any heap allocation.
+const STACK_LEN: usize = 32;
+
+/// How to choose between multiple matching suffixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SuffixMatch {
+    /// The suffix that was declared last wins, like in GNU `ls`.
+    #[default]
+    LastWins,
+
+    /// The longest suffix wins, so `*.tar.gz` beats `*.gz` regardless of the order. Among
+    /// suffixes of the same length, the one that was declared last wins.
+    Longest,
+}
+
+impl SuffixMatch {
+    /// The automaton match kind that implements this mode for reversed keys.
+    fn match_kind(self) -> MatchKind {
+        match self {
+            SuffixMatch::LastWins => MatchKind::LeftmostFirst,
+            SuffixMatch::Longest => MatchKind::LeftmostLongest,
+        }
+    }
+}
+
 /// A key in the suffix map.
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 struct SuffixKey {
@@ -49,6 +90,11 @@ impl SuffixKey {
         suffix.reverse();
         Self { rev_bytes: suffix }
     }
+
+    /// Check whether this is a version wildcard suffix (like `.so.*`).
+    fn is_versioned(&self) -> bool {
+        self.rev_bytes.len() > 2 && self.rev_bytes.starts_with(b"*.")
+    }
 }
 
 impl AsRef<[u8]> for SuffixKey {
@@ -66,36 +112,86 @@ pub struct SuffixMapBuilder {
     styles: Vec<Option<Style>>,
     /// The length of the longest suffix, in bytes.
     max_len: usize,
+    /// Whether there is a version wildcard suffix (like `.so.*`).
+    has_versioned: bool,
+    /// Whether all suffixes are matched case-sensitively.
+    case_sensitive: bool,
+    /// Whether case-insensitive matching uses Unicode (instead of ASCII) lowercasing.
+    unicode_case_insensitive: bool,
+    /// How to choose between multiple matching suffixes.
+    match_mode: SuffixMatch,
 }
 
 impl SuffixMapBuilder {
+    /// Match all suffixes case-sensitively (or use the default, mixed behavior).
+    pub fn case_sensitive(&mut self, case_sensitive: bool) {
+        self.case_sensitive = case_sensitive;
+    }
+
+    /// Lowercase suffixes and names with the Unicode lowercase mapping for case-insensitive
+    /// matching, instead of only the ASCII letters. This is more expensive, since the whole name
+    /// has to be lowercased (and copied to the heap) for every lookup.
+    pub fn unicode_case_insensitive(&mut self, unicode: bool) {
+        self.unicode_case_insensitive = unicode;
+    }
+
+    /// Choose how to pick between multiple matching suffixes.
+    pub fn match_mode(&mut self, match_mode: SuffixMatch) {
+        self.match_mode = match_mode;
+    }
+
     /// Add a new suffix to the map.
     pub fn push(&mut self, suffix: impl AsRef<[u8]>, style: Option<Style>) {
         let suffix = suffix.as_ref();
-        self.keys.push(SuffixKey::new(suffix));
+        let key = SuffixKey::new(suffix);
+        self.has_versioned |= key.is_versioned();
+        self.keys.push(key);
         self.styles.push(style);
         self.max_len = self.max_len.max(suffix.len());
     }
 
     /// Build the suffix map.
     pub fn build(mut self) -> SuffixMap {
-        // Reverse the lists, so that leftmost-*first* returns the *last* match instead
+        let versioned = self.has_versioned.then(|| self.build_versioned());
+
+        // Reverse the lists, so that leftmost-*first* returns the *last* match instead (this also
+        // breaks ties for leftmost-*longest*)
         self.keys.reverse();
         self.styles.reverse();
 
         // Build the case-sensitive matcher
         let cs_matcher = AhoCorasick::builder()
-            .match_kind(MatchKind::LeftmostFirst)
+            .match_kind(self.match_mode.match_kind())
             .start_kind(StartKind::Anchored)
             .build(&self.keys)
             .unwrap();
 
-        // Turn all the keys lowercase
-        let mut lower_keys = self.keys.clone();
-        for key in lower_keys.iter_mut() {
-            key.rev_bytes.make_ascii_lowercase();
+        if self.case_sensitive {
+            return SuffixMap {
+                cs_matcher,
+                ci_matcher: None,
+                keys: self.keys,
+                styles: self.styles,
+                ci_ids: vec![],
+                max_len: self.max_len,
+                versioned,
+                case_sensitive: true,
+                unicode_case_insensitive: self.unicode_case_insensitive,
+                match_mode: self.match_mode,
+            };
         }
 
+        // Turn all the keys lowercase
+        let lower_keys: Vec<_> = self
+            .keys
+            .iter()
+            .map(|key| {
+                let mut suffix = key.rev_bytes.to_vec();
+                suffix.reverse();
+                SuffixKey::new(&to_lowercase(&suffix, self.unicode_case_insensitive))
+            })
+            .collect();
+
         // Map keys to their first case-(in)sensitive occurrence
         let mut cs_map: HashMap<&SuffixKey, usize> = HashMap::new();
         let mut ci_map: HashMap<&SuffixKey, usize> = HashMap::new();
@@ -128,19 +224,93 @@ impl SuffixMapBuilder {
         // Build the case-insensitive matcher
         let ci_matcher = AhoCorasick::builder()
             .ascii_case_insensitive(true)
-            .match_kind(MatchKind::LeftmostFirst)
+            .match_kind(self.match_mode.match_kind())
             .start_kind(StartKind::Anchored)
             .build(ci_keys)
             .unwrap();
 
         SuffixMap {
             cs_matcher,
-            ci_matcher,
+            ci_matcher: Some(ci_matcher),
+            keys: self.keys,
             styles: self.styles,
             ci_ids,
             max_len: self.max_len,
+            versioned,
+            case_sensitive: false,
+            unicode_case_insensitive: self.unicode_case_insensitive,
+            match_mode: self.match_mode,
         }
     }
+
+    /// Build a map of only the version wildcard suffixes, together with the IDs of these suffixes
+    /// in the full map.
+    fn build_versioned(&self) -> (Box<SuffixMap>, Vec<usize>) {
+        let mut builder = SuffixMapBuilder {
+            case_sensitive: self.case_sensitive,
+            unicode_case_insensitive: self.unicode_case_insensitive,
+            match_mode: self.match_mode,
+            ..Default::default()
+        };
+
+        // The full map is built in reverse order
+        let mut ids = vec![];
+        for (i, (key, style)) in self.keys.iter().zip(self.styles.iter()).enumerate() {
+            if key.is_versioned() {
+                let mut suffix = key.rev_bytes.to_vec();
+                suffix.reverse();
+                builder.push(suffix, *style);
+                ids.push(self.keys.len() - 1 - i);
+            }
+        }
+        ids.reverse();
+
+        // The version wildcard is matched by the full map
+        builder.has_versioned = false;
+        (Box::new(builder.build()), ids)
+    }
+}
+
+/// Lowercase a suffix or name. Only ASCII letters are lowercased, unless `unicode` is set and the
+/// input is valid UTF-8.
+fn to_lowercase(s: &[u8], unicode: bool) -> Vec<u8> {
+    match std::str::from_utf8(s) {
+        Ok(s) if unicode => s.to_lowercase().into_bytes(),
+        _ => s.to_ascii_lowercase(),
+    }
+}
+
+/// A suffix that matched a file name, see [`LsColors::matched_suffix_for_str`].
+///
+/// [`LsColors::matched_suffix_for_str`]: crate::LsColors::matched_suffix_for_str
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchedSuffix<'a> {
+    /// The suffix pattern, reversed.
+    rev_pattern: &'a [u8],
+    /// The length of the matched part of the name, in bytes.
+    matched_len: usize,
+    /// The style of the suffix.
+    style: Option<&'a Style>,
+}
+
+impl<'a> MatchedSuffix<'a> {
+    /// Get the suffix pattern (without the leading `*`), like `.tar.gz` or `.so.*`.
+    pub fn pattern(&self) -> Vec<u8> {
+        let mut pattern = self.rev_pattern.to_vec();
+        pattern.reverse();
+        pattern
+    }
+
+    /// Get the length of the part of the name that the suffix matched, in bytes. This differs
+    /// from the length of the pattern for versioned suffixes (`.so.*` matches `.so.1.2`).
+    pub fn matched_len(&self) -> usize {
+        self.matched_len
+    }
+
+    /// Get the style of the suffix (`None` if the style resets all attributes, like `*.gz=0`).
+    pub fn style(&self) -> Option<&'a Style> {
+        self.style
+    }
 }
 
 /// Maps filename suffixes to styles.
@@ -148,27 +318,98 @@ impl SuffixMapBuilder {
 pub struct SuffixMap {
     /// Case-sensitive matcher.
     cs_matcher: AhoCorasick,
-    /// Case-insensitive suffixes.
-    ci_matcher: AhoCorasick,
+    /// Case-insensitive suffixes (if not all suffixes are case-sensitive).
+    ci_matcher: Option<AhoCorasick>,
+    /// List of keys (indexed by cs_matcher IDs)
+    keys: Vec<SuffixKey>,
     /// List of styles (indexed by cs_matcher IDs)
     styles: Vec<Option<Style>>,
     /// Map from ci_matcher to cs_matcher IDs.
     ci_ids: Vec<usize>,
     /// The length of the longest suffix, in bytes.
     max_len: usize,
+    /// The version wildcard suffixes (like `.so.*`), with their IDs in this map.
+    versioned: Option<(Box<SuffixMap>, Vec<usize>)>,
+    /// Whether all suffixes are matched case-sensitively.
+    case_sensitive: bool,
+    /// Whether case-insensitive matching uses Unicode (instead of ASCII) lowercasing.
+    unicode_case_insensitive: bool,
+    /// How to choose between multiple matching suffixes.
+    match_mode: SuffixMatch,
 }
 
 impl SuffixMap {
     /// Get the style for a matching suffix, if one exists.
     pub fn get(&self, name: impl AsRef<[u8]>) -> Option<&Style> {
-        let name = name.as_ref();
+        self.get_match(name)?.style()
+    }
+
+    /// Get the winning suffix and its style, if one matches.
+    pub fn get_match(&self, name: impl AsRef<[u8]>) -> Option<MatchedSuffix<'_>> {
+        let (index, matched_len) = self.index(name.as_ref())?;
+        Some(MatchedSuffix {
+            rev_pattern: &self.keys[index].rev_bytes,
+            matched_len,
+            style: self.styles[index].as_ref(),
+        })
+    }
+
+    /// Get the index of the winning suffix and the length of the matched part of the name, if
+    /// one matches.
+    fn index(&self, name: &[u8]) -> Option<(usize, usize)> {
+        let key_len = |i: usize| self.keys[i].rev_bytes.len();
+
+        let mut index = self.find_index(name).map(|i| (i, key_len(i)));
+        if let Some((versioned, ids)) = &self.versioned {
+            // Strip off version components one by one, and look for `<stem>.*`. Only the version
+            // wildcard suffixes are searched, since e.g. `*` or `.*` match this pattern as well.
+            let mut stem = name;
+            while let Some(version_start) = Self::version_start(stem) {
+                stem = &stem[..version_start];
+
+                let mut pattern = stem.to_vec();
+                pattern.extend_from_slice(b".*");
+                let Some(i) = versioned.find_index(&pattern).map(|i| ids[i]) else {
+                    continue;
+                };
 
-        // Split off only the longest suffix necessary
+                // The wildcard matches the version components
+                let matched_len = (key_len(i) + name.len() - stem.len()).checked_sub(2);
+                if let Some(matched_len) = matched_len {
+                    if self.better(index.map(|(j, _)| j), Some(i)) == Some(i) {
+                        index = Some((i, matched_len));
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    /// If the name ends in a numeric version component (like `.12`), return its start.
+    fn version_start(name: &[u8]) -> Option<usize> {
+        let digits = name.iter().rev().take_while(|b| b.is_ascii_digit()).count();
+        let dot = name.len().checked_sub(digits + 1)?;
+        (digits > 0 && dot > 0 && name[dot] == b'.').then_some(dot)
+    }
+
+    /// Get the index of the matching suffix, if one exists.
+    fn find_index(&self, name: &[u8]) -> Option<usize> {
+        // Lowercasing may change the length, so Unicode case-insensitive matching needs the whole
+        // name
+        let lower_name = (self.unicode_case_insensitive && self.ci_matcher.is_some()).then(|| {
+            let mut lower_name = to_lowercase(name, true);
+            lower_name.reverse();
+            lower_name
+        });
+
+        // Split off only the longest suffix necessary. This only depends on the longest configured
+        // suffix, not on the length of the name.
         let len = self.max_len.min(name.len());
         let i = name.len() - len;
 
         // Copy the suffix to the stack if small, otherwise the heap
-        let mut name_stack = [0; 32];
+        let mut name_stack = [0; STACK_LEN];
         let mut name_heap: Box<[u8]>;
 
         let name = if len <= name_stack.len() {
@@ -186,16 +427,86 @@ impl SuffixMap {
         let cs_index = Self::find(&self.cs_matcher, name);
 
         // Find a case-insensitive match
-        let ci_index = Self::find(&self.ci_matcher, name).map(|i| self.ci_ids[i]);
-
-        // Return the later match (earlier index)
-        let i = match (cs_index, ci_index) {
-            (Some(cs), Some(ci)) => cs.min(ci),
-            (Some(cs), _) => cs,
-            (_, Some(ci)) => ci,
-            (_, _) => return None,
-        };
-        self.styles[i].as_ref()
+        let ci_index = self
+            .ci_matcher
+            .as_ref()
+            .and_then(|ci_matcher| Self::find(ci_matcher, lower_name.as_deref().unwrap_or(name)))
+            .map(|i| self.ci_ids[i]);
+
+        self.better(cs_index, ci_index)
+    }
+
+    /// Choose the winning match among two (optional) matches.
+    fn better(&self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(match self.match_mode {
+                // Return the later match (earlier index)
+                SuffixMatch::LastWins => a.min(b),
+                // Return the longer match, or the later one if they have the same length
+                SuffixMatch::Longest => {
+                    let len = |i: usize| self.keys[i].rev_bytes.len();
+                    match len(a).cmp(&len(b)) {
+                        Ordering::Greater => a,
+                        Ordering::Less => b,
+                        Ordering::Equal => a.min(b),
+                    }
+                }
+            }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Get the length of the longest suffix, in bytes.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Check whether all suffixes are matched case-sensitively.
+    #[cfg(feature = "serde")]
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Check whether case-insensitive matching uses Unicode (instead of ASCII) lowercasing.
+    #[cfg(feature = "serde")]
+    pub fn is_unicode_case_insensitive(&self) -> bool {
+        self.unicode_case_insensitive
+    }
+
+    /// Get how to choose between multiple matching suffixes.
+    #[cfg(feature = "serde")]
+    pub fn match_mode(&self) -> SuffixMatch {
+        self.match_mode
+    }
+
+    /// Get mutable references to all styles.
+    pub fn styles_mut(&mut self) -> impl Iterator<Item = &mut Style> {
+        self.styles.iter_mut().flatten()
+    }
+
+    /// Iterate over all suffixes and their styles, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, Option<&Style>)> {
+        self.keys
+            .iter()
+            .zip(self.styles.iter())
+            .rev()
+            .map(|(key, style)| {
+                let mut suffix = key.rev_bytes.to_vec();
+                suffix.reverse();
+                (suffix, style.as_ref())
+            })
+    }
+
+    /// Get a builder with the same suffixes and settings.
+    pub fn to_builder(&self) -> SuffixMapBuilder {
+        let mut builder = SuffixMapBuilder::default();
+        builder.case_sensitive(self.case_sensitive);
+        builder.unicode_case_insensitive(self.unicode_case_insensitive);
+        builder.match_mode(self.match_mode);
+        for (suffix, style) in self.iter() {
+            builder.push(suffix, style.copied());
+        }
+        builder
     }
 
     /// Get the index of a match in a single matcher.
@@ -0,0 +1,67 @@
+//! Information about the terminal, derived from environment variables.
+
+use std::env;
+
+/// The brightness of the terminal background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Background {
+    /// A light background (dark text)
+    Light,
+
+    /// A dark background (light text)
+    Dark,
+
+    /// The background color is set, but can not be classified (e.g. `default`)
+    Unknown,
+}
+
+/// Detect the terminal background from the `COLORFGBG` environment variable, which is exported
+/// by terminals like rxvt, Konsole or iTerm2. Returns `None` if the variable is not set.
+///
+/// The variable contains the foreground and background colors as indices into the 16-color
+/// palette, e.g. `15;0` for white on black. Some terminals include a third value in the middle
+/// (`15;default;0`), so the background is always taken from the last value. The classification
+/// follows the same heuristic as Vim: the colors 0 to 6 (black to cyan) and 8 (bright black) are
+/// considered dark, all other palette colors are considered light.
+pub fn terminal_background() -> Option<Background> {
+    env::var("COLORFGBG")
+        .ok()
+        .map(|value| parse_colorfgbg(&value))
+}
+
+/// Classify the background from a `COLORFGBG` value.
+fn parse_colorfgbg(value: &str) -> Background {
+    let background = value.rsplit(';').next().unwrap_or_default();
+    match background.trim().parse::<u8>() {
+        Ok(0..=6 | 8) => Background::Dark,
+        Ok(7 | 9..=15) => Background::Light,
+        _ => Background::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorfgbg_two_values() {
+        assert_eq!(Background::Dark, parse_colorfgbg("15;0"));
+        assert_eq!(Background::Dark, parse_colorfgbg("7;8"));
+        assert_eq!(Background::Light, parse_colorfgbg("0;15"));
+        assert_eq!(Background::Light, parse_colorfgbg("0;7"));
+    }
+
+    #[test]
+    fn colorfgbg_three_values() {
+        assert_eq!(Background::Dark, parse_colorfgbg("15;default;0"));
+        assert_eq!(Background::Light, parse_colorfgbg("0;default;11"));
+    }
+
+    #[test]
+    fn colorfgbg_unknown() {
+        assert_eq!(Background::Unknown, parse_colorfgbg(""));
+        assert_eq!(Background::Unknown, parse_colorfgbg("15;default"));
+        assert_eq!(Background::Unknown, parse_colorfgbg("15;100"));
+        assert_eq!(Background::Unknown, parse_colorfgbg("foo"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Integration tests for the `lscolors` command-line application.
+
+use std::process::Command;
+
+fn lscolors() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_lscolors"));
+    cmd.env("LS_COLORS", "*.mp3=00;36");
+    cmd.env("CLICOLOR_FORCE", "1");
+    cmd.env_remove("NO_COLOR");
+    cmd
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[test]
+fn colors_arguments() {
+    let output = lscolors().arg("music.mp3").output().unwrap();
+    assert!(output.status.success());
+    assert!(contains(&output.stdout, b"\x1b["));
+    assert!(contains(&output.stdout, b"music.mp3"));
+}
+
+#[test]
+fn colors_from_flags() {
+    let output = lscolors()
+        .args(["--colors", "*.rs=01;31", "main.rs", "music.mp3"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(contains(&output.stdout, b"31mmain.rs"));
+    assert!(!contains(&output.stdout, b"36mmusic.mp3"));
+
+    let output = lscolors()
+        .env("MY_COLORS", "*.rs=01;31")
+        .args(["--colors-env=MY_COLORS", "--", "main.rs"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(contains(&output.stdout, b"31mmain.rs"));
+
+    let output = lscolors().arg("--colors").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn empty_style_prints_raw_text() {
+    let output = lscolors()
+        .args(["--colors", "*.txt=0;0", "notes.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(b"notes.txt\n", output.stdout.as_slice());
+}
+
+#[test]
+fn color_modes() {
+    let colored = |cmd: &mut std::process::Command| {
+        let output = cmd.arg("music.mp3").output().unwrap();
+        assert!(output.status.success());
+        contains(&output.stdout, b"\x1b[")
+    };
+
+    // Output is not a terminal
+    assert!(!colored(lscolors().env_remove("CLICOLOR_FORCE")));
+    assert!(!colored(lscolors().env("CLICOLOR_FORCE", "0")));
+    assert!(colored(
+        lscolors()
+            .arg("--color=always")
+            .env_remove("CLICOLOR_FORCE")
+    ));
+
+    assert!(!colored(
+        lscolors().env("NO_COLOR", "1").env_remove("CLICOLOR_FORCE")
+    ));
+    assert!(colored(lscolors().env("NO_COLOR", "")));
+    assert!(colored(
+        lscolors().env("NO_COLOR", "1").args(["--color", "always"])
+    ));
+    assert!(!colored(lscolors().arg("--color=never")));
+
+    // Paths are printed verbatim without colors
+    let output = lscolors()
+        .args(["--color=never", "./music//song.mp3"])
+        .output()
+        .unwrap();
+    assert_eq!(b"./music//song.mp3\n", output.stdout.as_slice());
+
+    let output = lscolors().arg("--color=sometimes").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn classify() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let file = tmp_dir.path().join("music.mp3");
+    std::fs::File::create(&file).unwrap();
+
+    let output = lscolors()
+        .arg("--classify")
+        .arg(tmp_dir.path())
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert!(lines[0].ends_with('/'));
+    assert!(lines[1].ends_with('m'));
+
+    let output = lscolors().arg(tmp_dir.path()).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.trim_end().ends_with('/'));
+}
+
+#[cfg(unix)]
+#[test]
+fn classify_without_colors() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let script = tmp_dir.path().join("run.sh");
+    std::fs::File::create(&script).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = lscolors()
+        .args(["--classify", "--color=never", "--colors=ex=00"])
+        .arg(&script)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.ends_with(b"run.sh*\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn classify_symlinks() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let link = tmp_dir.path().join("link");
+    std::os::unix::fs::symlink("missing", &link).unwrap();
+
+    let output = lscolors()
+        .args(["--classify", "--color=never"])
+        .arg(&link)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.ends_with(b"link@\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn preserves_non_utf8_arguments() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let name = b"caf\xc3\xa9\xff.mp3";
+    let output = lscolors().arg(OsStr::from_bytes(name)).output().unwrap();
+    assert!(output.status.success());
+    assert!(contains(&output.stdout, name));
+    assert!(contains(&output.stdout, b"\x1b["));
+}
+
+#[cfg(unix)]
+#[test]
+fn preserves_non_utf8_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = lscolors()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"some/dir/\xff\xfe.mp3\nother.txt\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert!(contains(&output.stdout, b"\xff\xfe.mp3"));
+    assert!(contains(&output.stdout, b"other.txt\n"));
+}
+
+#[test]
+fn nul_separated() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = lscolors()
+        .args(["-0", "--print0", "--color=never"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"new\nline.mp3\0other.txt\0last.txt")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        b"new\nline.mp3\0other.txt\0last.txt\0",
+        output.stdout.as_slice()
+    );
+}
+
+#[test]
+fn unterminated_last_line() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = lscolors()
+        .arg("--color=never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"music.mp3\nother.txt")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(b"music.mp3\nother.txt\n", output.stdout.as_slice());
+}
+
+#[test]
+fn help() {
+    for flag in ["-h", "--help"] {
+        let output = lscolors().arg(flag).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("Usage: lscolors"));
+        assert!(stdout.contains("--version"));
+    }
+}
+
+#[test]
+fn version() {
+    for flag in ["-V", "--version"] {
+        let output = lscolors().arg(flag).output().unwrap();
+        assert!(output.status.success());
+        let expected = format!("lscolors {}\n", env!("CARGO_PKG_VERSION"));
+        assert_eq!(expected.as_bytes(), output.stdout.as_slice());
+    }
+}
+
+#[test]
+fn paths_after_double_dash() {
+    let output = lscolors()
+        .args(["--color=never", "--", "--help", "-V"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(b"--help\n-V\n", output.stdout.as_slice());
+}
+
+#[test]
+fn only_basename() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let dir = tmp_dir.path().join("music");
+    std::fs::create_dir(&dir).unwrap();
+    let path = dir.join("song.mp3");
+
+    let output = lscolors().arg(&path).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.matches("\x1b[").count() > 2);
+
+    let output = lscolors()
+        .arg("--only-basename")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (dirs, basename) = stdout.split_at(stdout.find("\x1b[").unwrap());
+    assert!(dirs.ends_with(&format!("music{}", std::path::MAIN_SEPARATOR)));
+    assert!(basename.contains("song.mp3"));
+}